@@ -213,6 +213,7 @@ export class ContractService {
       StellarSdk.Address.fromString(sourceAccount).toScVal(),
       StellarSdk.nativeToScVal(BigInt(price), { type: 'i128' }),
       StellarSdk.nativeToScVal(null),
+      StellarSdk.nativeToScVal(null), // reserved_buyer
     ];
 
     return await this.prepareContractTransaction(
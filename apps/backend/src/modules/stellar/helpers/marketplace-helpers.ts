@@ -109,6 +109,7 @@ export async function buildBuyTransaction(params: {
         'buy',
         listingIdVal,
         buyerAddress.toScVal(),
+        StellarSdk.nativeToScVal(null), // referrer
       ),
     )
     .setTimeout(30)
@@ -371,6 +371,7 @@ class StellarWalletService {
         new Address(this.state.publicKey),
         BigInt(price),
         expiresAt || null,
+        null, // reserved_buyer
       ],
     });
 
@@ -392,6 +393,7 @@ class StellarWalletService {
       args: [
         listingId,
         new Address(this.state.publicKey),
+        null, // referrer
       ],
     });
   }
@@ -0,0 +1,143 @@
+#![no_std]
+
+//! Multisig / timelock governance for admin operations
+//!
+//! Shared by the Nuna Curate contracts. Once the admin enables governance,
+//! admin functions can no longer be called directly. Instead a signer
+//! proposes an admin action, other signers approve it, and once `threshold`
+//! approvals are in the timelock starts. After `delay` seconds anyone can
+//! execute the proposal. Changing or disabling the config cancels every
+//! proposal still pending, since its approvals came from the old signers.
+//!
+//! Each contract keeps its own `Proposal` type (its admin actions differ)
+//! and error enum; failures here are `GovernanceError`s for the contract to
+//! convert.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+pub const MAX_SIGNERS: u32 = 20;
+
+/// M-of-N signers and timelock that govern admin actions
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GovernanceConfig {
+    pub signers: Vec<Address>,
+    /// Approvals needed before the timelock starts
+    pub threshold: u32,
+    /// Seconds between reaching the threshold and execution
+    pub delay: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    Pending,
+    Executed,
+    Cancelled,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GovernanceError {
+    /// Caller is not a signer
+    Unauthorized,
+    InvalidGovernance,
+    InvalidProposal,
+}
+
+/// Check signers are distinct (1-20) and the threshold is reachable
+pub fn validate_config(config: &GovernanceConfig) -> Result<(), GovernanceError> {
+    let signer_count = config.signers.len();
+    if signer_count == 0 || signer_count > MAX_SIGNERS {
+        return Err(GovernanceError::InvalidGovernance);
+    }
+
+    if config.threshold == 0 || config.threshold > signer_count {
+        return Err(GovernanceError::InvalidGovernance);
+    }
+
+    for (index, signer) in config.signers.iter().enumerate() {
+        if config.signers.iter().skip(index + 1).any(|other| other == signer) {
+            return Err(GovernanceError::InvalidGovernance);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn check_signer(config: &GovernanceConfig, signer: &Address) -> Result<(), GovernanceError> {
+    if !config.signers.contains(signer) {
+        return Err(GovernanceError::Unauthorized);
+    }
+
+    Ok(())
+}
+
+pub fn check_pending(status: &ProposalStatus) -> Result<(), GovernanceError> {
+    if *status != ProposalStatus::Pending {
+        return Err(GovernanceError::InvalidProposal);
+    }
+
+    Ok(())
+}
+
+/// A stored proposal's status, given the first proposal ID made under the
+/// current config: older pending proposals count as cancelled
+pub fn current_status(status: ProposalStatus, proposal_id: u64, first_current_id: u64) -> ProposalStatus {
+    if status == ProposalStatus::Pending && proposal_id < first_current_id {
+        ProposalStatus::Cancelled
+    } else {
+        status
+    }
+}
+
+/// Add a signer's approval; starts the timelock when the threshold is met
+pub fn record_approval(
+    env: &Env,
+    config: &GovernanceConfig,
+    approvals: &mut Vec<Address>,
+    executable_at: &mut Option<u64>,
+    signer: &Address,
+) -> Result<(), GovernanceError> {
+    if approvals.contains(signer) {
+        return Err(GovernanceError::InvalidProposal);
+    }
+
+    approvals.push_back(signer.clone());
+
+    if executable_at.is_none() && approval_count(config, approvals) >= config.threshold {
+        *executable_at = Some(env.ledger().timestamp() + config.delay);
+    }
+
+    Ok(())
+}
+
+/// Check the timelock has passed and the approvals still meet the
+/// threshold (signers may have changed since they approved)
+pub fn check_executable(
+    env: &Env,
+    config: &GovernanceConfig,
+    status: &ProposalStatus,
+    approvals: &Vec<Address>,
+    executable_at: Option<u64>,
+) -> Result<(), GovernanceError> {
+    check_pending(status)?;
+
+    match executable_at {
+        Some(time) if env.ledger().timestamp() >= time => {}
+        _ => return Err(GovernanceError::InvalidProposal),
+    }
+
+    if approval_count(config, approvals) < config.threshold {
+        return Err(GovernanceError::InvalidProposal);
+    }
+
+    Ok(())
+}
+
+/// Approvals from current signers
+fn approval_count(config: &GovernanceConfig, approvals: &Vec<Address>) -> u32 {
+    approvals
+        .iter()
+        .filter(|signer| config.signers.contains(signer))
+        .count() as u32
+}
@@ -0,0 +1,74 @@
+//! Sealed-bid (commit-reveal) auctions
+//!
+//! Bidders commit `hash_bid(amount, salt, bidder)` with a fixed deposit while
+//! bidding is open, then reveal the amount and salt during the reveal window.
+//! A reveal tops the bidder's escrow up to the bid amount, so the winner can
+//! always be settled. Once the auction is settled (or cancelled), every bid is
+//! released: refunded to the bidder, or forfeited to the seller if it was
+//! never revealed and the auction's policy says so.
+
+use soroban_sdk::{xdr::ToXdr, Address, Bytes, BytesN, Env};
+use crate::{AuctionPricing, AuctionStatus, SealedAuction, errors::Error};
+
+/// Commitment for a sealed bid
+/// sha256(amount as 16-byte big-endian || salt || bidder address XDR)
+pub fn hash_bid(env: &Env, amount: i128, salt: &BytesN<32>, bidder: &Address) -> BytesN<32> {
+    let mut payload = Bytes::from_array(env, &amount.to_be_bytes());
+    payload.append(&salt.clone().into());
+    payload.append(&bidder.clone().to_xdr(env));
+
+    env.crypto().sha256(&payload).into()
+}
+
+/// Check bids can still be committed
+pub fn check_bidding_open(env: &Env, auction: &SealedAuction) -> Result<(), Error> {
+    if auction.status != AuctionStatus::Open {
+        return Err(Error::AuctionNotOpen);
+    }
+
+    if env.ledger().timestamp() > auction.terms.bid_end {
+        return Err(Error::WrongAuctionPhase);
+    }
+
+    Ok(())
+}
+
+/// Check the auction is between the end of bidding and the end of reveals
+pub fn check_reveal_open(env: &Env, auction: &SealedAuction) -> Result<(), Error> {
+    if auction.status != AuctionStatus::Open {
+        return Err(Error::AuctionNotOpen);
+    }
+
+    let now = env.ledger().timestamp();
+    if now <= auction.terms.bid_end || now > auction.terms.reveal_end {
+        return Err(Error::WrongAuctionPhase);
+    }
+
+    Ok(())
+}
+
+/// Record a revealed bid, tracking the highest and second-highest valid bids
+/// Bids below the reserve are refunded but never win. Ties go to the
+/// earlier reveal.
+pub fn record_reveal(auction: &mut SealedAuction, bidder: &Address, amount: i128) {
+    if amount < auction.terms.reserve_price {
+        return;
+    }
+
+    if auction.highest_bidder.is_none() || amount > auction.highest_bid {
+        auction.second_bid = auction.highest_bid;
+        auction.highest_bid = amount;
+        auction.highest_bidder = Some(bidder.clone());
+    } else if amount > auction.second_bid {
+        auction.second_bid = amount;
+    }
+}
+
+/// Price the winner pays
+/// Second-price auctions never clear below the reserve.
+pub fn clearing_price(auction: &SealedAuction) -> i128 {
+    match auction.terms.pricing {
+        AuctionPricing::FirstPrice => auction.highest_bid,
+        AuctionPricing::SecondPrice => auction.second_bid.max(auction.terms.reserve_price),
+    }
+}
@@ -1,3 +1,4 @@
+use nuna_governance::GovernanceError;
 use soroban_sdk::contracterror;
 
 #[contracterror]
@@ -12,21 +13,86 @@ pub enum Error {
     ListingNotFound = 10,
     ListingNotActive = 11,
     ListingExpired = 12,
+    ListingStillValid = 13,
 
     // Offers
     OfferNotFound = 20,
     OfferExpired = 21,
+    CounterOfferNotFound = 22,
 
     // Authorization
     Unauthorized = 30,
     CannotBuyOwnListing = 31,
+    NotReservedBuyer = 32,
 
     // Validation
     InvalidPrice = 40,
     InvalidFee = 41,
     InvalidExpiry = 42,
+    InvalidRoyalty = 43,
 
     // Transfer errors
     TransferFailed = 50,
     InsufficientBalance = 51,
+
+    // Signed orders
+    OrderExpired = 60,
+    OrderCancelled = 61,
+    OrderAlreadyFilled = 62,
+    InvalidOrderNonce = 63,
+    OrderSignerNotSet = 64,
+
+    // Batch purchases
+    BatchEmpty = 70,
+    BatchTooLarge = 71,
+    MaxTotalExceeded = 72,
+
+    // Bundles
+    InvalidBundle = 80,
+    NotTokenOwner = 81,
+    MarketplaceNotApproved = 82,
+
+    // Swaps
+    SwapNotFound = 90,
+    SwapNotOpen = 91,
+    SwapExpired = 92,
+    InvalidSwap = 93,
+
+    // Payouts
+    NothingToWithdraw = 100,
+
+    // Collection registry
+    CollectionBlocked = 110,
+    CollectionNotAllowed = 111,
+
+    // Sealed-bid auctions
+    AuctionNotFound = 120,
+    AuctionNotOpen = 121,
+    WrongAuctionPhase = 122,
+    AuctionHasBids = 123,
+    AlreadyCommitted = 124,
+    BidNotFound = 125,
+    InvalidReveal = 126,
+
+    // Raffles
+    RaffleNotFound = 130,
+    RaffleClosed = 131,
+    RaffleNotEnded = 132,
+    RaffleSoldOut = 133,
+    InvalidRaffle = 134,
+
+    // Governance (the spec allows at most 50 error codes; other
+    // governance failures use Unauthorized)
+    InvalidGovernance = 140,
+    InvalidProposal = 141,
+}
+
+impl From<GovernanceError> for Error {
+    fn from(err: GovernanceError) -> Self {
+        match err {
+            GovernanceError::Unauthorized => Error::Unauthorized,
+            GovernanceError::InvalidGovernance => Error::InvalidGovernance,
+            GovernanceError::InvalidProposal => Error::InvalidProposal,
+        }
+    }
 }
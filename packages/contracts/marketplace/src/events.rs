@@ -1,14 +1,60 @@
-use soroban_sdk::{Address, Env, Symbol, symbol_short};
+use soroban_sdk::{Address, BytesN, Env, Symbol, Vec, symbol_short};
+use crate::{AdminAction, AuctionStatus, CollectionStatus, CounterOffer, FeeQuote, FeeTier, GovernanceConfig, Listing, Offer, Order, PayoutMode, RaffleStatus, RaffleTerms, ScheduledFeeChange, SealedAuctionTerms, Swap, TradingMode};
 
 // Event topics
 const MARKETPLACE_INIT: Symbol = symbol_short!("mrkt_init");
 const LISTING_CREATED: Symbol = symbol_short!("list_new");
+const BUNDLE_LISTING_CREATED: Symbol = symbol_short!("list_bndl");
+const LISTING_UPDATED: Symbol = symbol_short!("list_upd");
 const LISTING_CANCELLED: Symbol = symbol_short!("list_cnl");
+const LISTING_INVALIDATED: Symbol = symbol_short!("list_inv");
+const LISTING_EXPIRED: Symbol = symbol_short!("list_exp");
 const SALE: Symbol = symbol_short!("sale");
+const BATCH_BUY: Symbol = symbol_short!("batch_buy");
 const OFFER_MADE: Symbol = symbol_short!("offr_made");
 const OFFER_ACCEPTED: Symbol = symbol_short!("offr_acc");
 const OFFER_CANCELLED: Symbol = symbol_short!("offr_cnl");
+const OFFER_EXPIRED: Symbol = symbol_short!("offr_exp");
+const COUNTER_OFFER_MADE: Symbol = symbol_short!("ctr_made");
+const COUNTER_OFFER_ACCEPTED: Symbol = symbol_short!("ctr_acc");
+const COUNTER_OFFER_CANCELLED: Symbol = symbol_short!("ctr_cnl");
+const AUCTION_CREATED: Symbol = symbol_short!("auct_new");
+const AUCTION_SETTLED: Symbol = symbol_short!("auct_end");
+const AUCTION_CANCELLED: Symbol = symbol_short!("auct_cnl");
+const BID_COMMITTED: Symbol = symbol_short!("bid_cmt");
+const BID_REVEALED: Symbol = symbol_short!("bid_rvl");
+const BID_RELEASED: Symbol = symbol_short!("bid_rel");
+const RAFFLE_CREATED: Symbol = symbol_short!("rfl_new");
+const TICKETS_BOUGHT: Symbol = symbol_short!("rfl_buy");
+const RAFFLE_DRAWN: Symbol = symbol_short!("rfl_draw");
+const TICKETS_REFUNDED: Symbol = symbol_short!("rfl_rfnd");
+const GOVERNANCE_SET: Symbol = symbol_short!("gov_set");
+const PROPOSAL_CREATED: Symbol = symbol_short!("prop_new");
+const PROPOSAL_APPROVED: Symbol = symbol_short!("prop_appr");
+const PROPOSAL_EXECUTED: Symbol = symbol_short!("prop_exec");
+const PROPOSAL_CANCELLED: Symbol = symbol_short!("prop_cnl");
 const FEE_UPDATED: Symbol = symbol_short!("fee_upd");
+const FEE_CHANGE_SCHEDULED: Symbol = symbol_short!("fee_sched");
+const MAX_ROYALTY_UPDATED: Symbol = symbol_short!("roy_max");
+const FEE_RECIPIENT_UPDATED: Symbol = symbol_short!("fee_rcpt");
+const SWAP_PROPOSED: Symbol = symbol_short!("swap_new");
+const SWAP_ACCEPTED: Symbol = symbol_short!("swap_acc");
+const SWAP_CANCELLED: Symbol = symbol_short!("swap_cnl");
+const ORDER_SIGNER_SET: Symbol = symbol_short!("ord_key");
+const ORDER_FILLED: Symbol = symbol_short!("ord_fill");
+const ORDER_CANCELLED: Symbol = symbol_short!("ord_cnl");
+const NONCE_INCREMENTED: Symbol = symbol_short!("nonce_inc");
+const PAYOUT_MODE_SET: Symbol = symbol_short!("pay_mode");
+const WITHDRAWN: Symbol = symbol_short!("withdrawn");
+const COLLECTION_STATUS_SET: Symbol = symbol_short!("coll_stat");
+const COLLECTION_FEE_SET: Symbol = symbol_short!("coll_fee");
+const COLLECTION_FEE_SCHEDULED: Symbol = symbol_short!("cfee_schd");
+const TRADING_MODE_SET: Symbol = symbol_short!("trd_mode");
+const FEE_TIERS_SET: Symbol = symbol_short!("fee_tiers");
+const MEMBERSHIP_SET: Symbol = symbol_short!("member");
+const REFERRAL_SHARE_SET: Symbol = symbol_short!("ref_share");
+const REFERRER_REGISTERED: Symbol = symbol_short!("ref_reg");
+const REFERRER_REMOVED: Symbol = symbol_short!("ref_rm");
 
 pub fn emit_marketplace_initialized(env: &Env, admin: &Address, fee_bps: u32) {
     env.events().publish((MARKETPLACE_INIT,), (admin, fee_bps));
@@ -21,34 +67,74 @@ pub fn emit_listing_created(
     token_id: u64,
     seller: &Address,
     price: i128,
+    reserved_buyer: &Option<Address>,
 ) {
     env.events().publish(
         (LISTING_CREATED, seller),
-        (listing_id, nft_contract, token_id, price),
+        (listing_id, nft_contract, token_id, price, reserved_buyer.clone()),
     );
 }
 
-pub fn emit_listing_cancelled(env: &Env, listing_id: u64, seller: &Address) {
-    env.events().publish((LISTING_CANCELLED, seller), listing_id);
+pub fn emit_bundle_listing_created(
+    env: &Env,
+    listing_id: u64,
+    items: &Vec<(Address, u64)>,
+    seller: &Address,
+    price: i128,
+    reserved_buyer: &Option<Address>,
+) {
+    env.events().publish(
+        (BUNDLE_LISTING_CREATED, seller),
+        (listing_id, items.clone(), price, reserved_buyer.clone()),
+    );
 }
 
-pub fn emit_sale(
+pub fn emit_listing_updated(
     env: &Env,
     listing_id: u64,
-    nft_contract: &Address,
-    token_id: u64,
     seller: &Address,
-    buyer: &Address,
-    price: i128,
-    platform_fee: i128,
-    royalty_fee: i128,
+    old_price: i128,
+    new_price: i128,
+    new_expires_at: Option<u64>,
 ) {
     env.events().publish(
-        (SALE, seller, buyer),
-        (listing_id, nft_contract, token_id, price, platform_fee, royalty_fee),
+        (LISTING_UPDATED, seller),
+        (listing_id, old_price, new_price, new_expires_at),
     );
 }
 
+pub fn emit_listing_cancelled(env: &Env, listing_id: u64, seller: &Address) {
+    env.events().publish((LISTING_CANCELLED, seller), listing_id);
+}
+
+pub fn emit_listing_invalidated(env: &Env, listing_id: u64, seller: &Address) {
+    env.events().publish((LISTING_INVALIDATED, seller), listing_id);
+}
+
+pub fn emit_listing_expired(env: &Env, listing_id: u64, seller: &Address) {
+    env.events().publish((LISTING_EXPIRED, seller), listing_id);
+}
+
+pub fn emit_sale(env: &Env, listing: &Listing, buyer: &Address, split: &FeeQuote) {
+    env.events().publish(
+        (SALE, &listing.seller, buyer),
+        (
+            listing.listing_id,
+            &listing.nft_contract,
+            listing.token_id,
+            listing.price,
+            split.platform_fee,
+            split.royalty_amount,
+            split.referrer.clone(),
+            split.referral_fee,
+        ),
+    );
+}
+
+pub fn emit_batch_buy(env: &Env, buyer: &Address, purchased: u32, total_spent: i128) {
+    env.events().publish((BATCH_BUY, buyer), (purchased, total_spent));
+}
+
 pub fn emit_offer_made(
     env: &Env,
     offer_id: u64,
@@ -63,25 +149,265 @@ pub fn emit_offer_made(
     );
 }
 
-pub fn emit_offer_accepted(
+pub fn emit_offer_accepted(env: &Env, offer: &Offer, seller: &Address, split: &FeeQuote) {
+    env.events().publish(
+        (OFFER_ACCEPTED, seller, &offer.buyer),
+        (
+            offer.offer_id,
+            &offer.nft_contract,
+            offer.token_id,
+            offer.amount,
+            split.referrer.clone(),
+            split.referral_fee,
+        ),
+    );
+}
+
+pub fn emit_offer_cancelled(env: &Env, offer_id: u64, buyer: &Address) {
+    env.events().publish((OFFER_CANCELLED, buyer), offer_id);
+}
+
+pub fn emit_offer_expired(env: &Env, offer_id: u64, buyer: &Address) {
+    env.events().publish((OFFER_EXPIRED, buyer), offer_id);
+}
+
+pub fn emit_counter_offer_made(
+    env: &Env,
+    counter_id: u64,
+    thread_id: u64,
+    seller: &Address,
+    buyer: &Address,
+    amount: i128,
+) {
+    env.events().publish(
+        (COUNTER_OFFER_MADE, seller, buyer),
+        (counter_id, thread_id, amount),
+    );
+}
+
+pub fn emit_counter_offer_accepted(env: &Env, counter: &CounterOffer, escrowed: i128) {
+    env.events().publish(
+        (COUNTER_OFFER_ACCEPTED, &counter.seller, &counter.buyer),
+        (counter.counter_id, counter.thread_id, counter.amount, escrowed),
+    );
+}
+
+pub fn emit_counter_offer_cancelled(env: &Env, counter_id: u64, thread_id: u64, by: &Address) {
+    env.events().publish((COUNTER_OFFER_CANCELLED, by), (counter_id, thread_id));
+}
+
+pub fn emit_auction_created(
     env: &Env,
-    offer_id: u64,
+    auction_id: u64,
     nft_contract: &Address,
     token_id: u64,
     seller: &Address,
-    buyer: &Address,
-    amount: i128,
+    terms: &SealedAuctionTerms,
 ) {
     env.events().publish(
-        (OFFER_ACCEPTED, seller, buyer),
-        (offer_id, nft_contract, token_id, amount),
+        (AUCTION_CREATED, seller),
+        (auction_id, nft_contract, token_id, terms.clone()),
     );
 }
 
-pub fn emit_offer_cancelled(env: &Env, offer_id: u64, buyer: &Address) {
-    env.events().publish((OFFER_CANCELLED, buyer), offer_id);
+pub fn emit_bid_committed(env: &Env, auction_id: u64, bidder: &Address) {
+    env.events().publish((BID_COMMITTED, bidder), auction_id);
+}
+
+pub fn emit_bid_revealed(env: &Env, auction_id: u64, bidder: &Address, amount: i128) {
+    env.events().publish((BID_REVEALED, bidder), (auction_id, amount));
+}
+
+pub fn emit_auction_settled(
+    env: &Env,
+    auction_id: u64,
+    status: &AuctionStatus,
+    winner: &Option<Address>,
+    price: i128,
+) {
+    env.events().publish(
+        (AUCTION_SETTLED,),
+        (auction_id, status.clone(), winner.clone(), price),
+    );
+}
+
+pub fn emit_auction_cancelled(env: &Env, auction_id: u64, seller: &Address) {
+    env.events().publish((AUCTION_CANCELLED, seller), auction_id);
+}
+
+pub fn emit_bid_released(env: &Env, auction_id: u64, bidder: &Address, refund: i128, forfeited: bool) {
+    env.events().publish((BID_RELEASED, bidder), (auction_id, refund, forfeited));
+}
+
+pub fn emit_raffle_created(
+    env: &Env,
+    raffle_id: u64,
+    nft_contract: &Address,
+    token_id: u64,
+    seller: &Address,
+    terms: &RaffleTerms,
+) {
+    env.events().publish(
+        (RAFFLE_CREATED, seller),
+        (raffle_id, nft_contract, token_id, terms.clone()),
+    );
+}
+
+pub fn emit_tickets_bought(env: &Env, raffle_id: u64, buyer: &Address, count: u32) {
+    env.events().publish((TICKETS_BOUGHT, buyer), (raffle_id, count));
+}
+
+pub fn emit_raffle_drawn(
+    env: &Env,
+    raffle_id: u64,
+    status: &RaffleStatus,
+    winner: &Option<Address>,
+    tickets_sold: u32,
+) {
+    env.events().publish(
+        (RAFFLE_DRAWN,),
+        (raffle_id, status.clone(), winner.clone(), tickets_sold),
+    );
+}
+
+pub fn emit_tickets_refunded(env: &Env, raffle_id: u64, buyer: &Address, amount: i128) {
+    env.events().publish((TICKETS_REFUNDED, buyer), (raffle_id, amount));
 }
 
 pub fn emit_fee_updated(env: &Env, new_fee_bps: u32) {
     env.events().publish((FEE_UPDATED,), new_fee_bps);
 }
+
+/// None when a scheduled change is cancelled
+pub fn emit_fee_change_scheduled(env: &Env, change: &Option<ScheduledFeeChange>) {
+    env.events().publish((FEE_CHANGE_SCHEDULED,), change.clone());
+}
+
+pub fn emit_max_royalty_updated(env: &Env, max_royalty_bps: u32) {
+    env.events().publish((MAX_ROYALTY_UPDATED,), max_royalty_bps);
+}
+
+pub fn emit_fee_recipient_updated(env: &Env, new_recipient: &Address) {
+    env.events().publish((FEE_RECIPIENT_UPDATED,), new_recipient);
+}
+
+pub fn emit_swap_proposed(env: &Env, swap: &Swap) {
+    env.events().publish(
+        (SWAP_PROPOSED, swap.maker.clone(), swap.taker.clone()),
+        (
+            swap.swap_id,
+            swap.maker_items.clone(),
+            swap.payment_amount,
+            swap.taker_items.clone(),
+        ),
+    );
+}
+
+pub fn emit_swap_accepted(env: &Env, swap_id: u64, maker: &Address, taker: &Address) {
+    env.events().publish((SWAP_ACCEPTED, maker, taker), swap_id);
+}
+
+pub fn emit_swap_cancelled(env: &Env, swap_id: u64, maker: &Address) {
+    env.events().publish((SWAP_CANCELLED, maker), swap_id);
+}
+
+pub fn emit_order_signer_set(env: &Env, seller: &Address, public_key: &BytesN<32>) {
+    env.events().publish((ORDER_SIGNER_SET, seller), public_key.clone());
+}
+
+pub fn emit_order_filled(
+    env: &Env,
+    order_hash: &BytesN<32>,
+    order: &Order,
+    buyer: &Address,
+    platform_fee: i128,
+    royalty_fee: i128,
+) {
+    env.events().publish(
+        (ORDER_FILLED, &order.seller, buyer),
+        (
+            order_hash.clone(),
+            &order.nft_contract,
+            order.token_id,
+            order.price,
+            platform_fee,
+            royalty_fee,
+        ),
+    );
+}
+
+pub fn emit_order_cancelled(env: &Env, order_hash: &BytesN<32>, seller: &Address) {
+    env.events().publish((ORDER_CANCELLED, seller), order_hash.clone());
+}
+
+pub fn emit_nonce_incremented(env: &Env, seller: &Address, new_nonce: u64) {
+    env.events().publish((NONCE_INCREMENTED, seller), new_nonce);
+}
+
+pub fn emit_payout_mode_set(env: &Env, recipient: &Address, mode: PayoutMode) {
+    env.events().publish((PAYOUT_MODE_SET, recipient), mode);
+}
+
+pub fn emit_withdrawn(env: &Env, recipient: &Address, token: &Address, amount: i128) {
+    env.events().publish((WITHDRAWN, recipient), (token, amount));
+}
+
+pub fn emit_collection_status_set(env: &Env, nft_contract: &Address, status: &Option<CollectionStatus>) {
+    env.events().publish((COLLECTION_STATUS_SET, nft_contract), *status);
+}
+
+pub fn emit_collection_fee_set(env: &Env, nft_contract: &Address, fee_bps: Option<u32>) {
+    env.events().publish((COLLECTION_FEE_SET, nft_contract), fee_bps);
+}
+
+pub fn emit_collection_fee_change_scheduled(
+    env: &Env,
+    nft_contract: &Address,
+    change: &Option<ScheduledFeeChange>,
+) {
+    env.events().publish((COLLECTION_FEE_SCHEDULED, nft_contract), change.clone());
+}
+
+pub fn emit_trading_mode_set(env: &Env, mode: TradingMode) {
+    env.events().publish((TRADING_MODE_SET,), mode);
+}
+
+pub fn emit_fee_tiers_set(env: &Env, tiers: &Vec<FeeTier>) {
+    env.events().publish((FEE_TIERS_SET,), tiers.clone());
+}
+
+pub fn emit_membership_discount_set(env: &Env, membership: &Option<(Address, u32)>) {
+    env.events().publish((MEMBERSHIP_SET,), membership.clone());
+}
+
+pub fn emit_referral_share_set(env: &Env, share_bps: u32) {
+    env.events().publish((REFERRAL_SHARE_SET,), share_bps);
+}
+
+pub fn emit_referrer_registered(env: &Env, referrer: &Address, share_bps: Option<u32>) {
+    env.events().publish((REFERRER_REGISTERED, referrer), share_bps);
+}
+
+pub fn emit_referrer_removed(env: &Env, referrer: &Address) {
+    env.events().publish((REFERRER_REMOVED, referrer), ());
+}
+
+pub fn emit_governance_set(env: &Env, config: &Option<GovernanceConfig>) {
+    env.events().publish((GOVERNANCE_SET,), config.clone());
+}
+
+pub fn emit_proposal_created(env: &Env, proposal_id: u64, proposer: &Address, action: &AdminAction) {
+    env.events().publish((PROPOSAL_CREATED, proposer), (proposal_id, action.clone()));
+}
+
+pub fn emit_proposal_approved(env: &Env, proposal_id: u64, signer: &Address, executable_at: Option<u64>) {
+    env.events().publish((PROPOSAL_APPROVED, signer), (proposal_id, executable_at));
+}
+
+pub fn emit_proposal_executed(env: &Env, proposal_id: u64) {
+    env.events().publish((PROPOSAL_EXECUTED,), proposal_id);
+}
+
+pub fn emit_proposal_cancelled(env: &Env, proposal_id: u64, signer: &Address) {
+    env.events().publish((PROPOSAL_CANCELLED, signer), proposal_id);
+}
@@ -0,0 +1,54 @@
+//! Scheduled platform fee changes
+//!
+//! Fee increases have to be announced: a change is scheduled at least
+//! `FEE_CHANGE_NOTICE` ahead, moves the fee by at most `MAX_FEE_CHANGE_BPS`,
+//! and takes effect no sooner than `FEE_CHANGE_PERIOD` after the previous
+//! scheduled change. Decreases may still be applied immediately. The same
+//! rules apply to the global fee and to each collection's override.
+//!
+//! Listings, sealed auctions and raffles lock the fee in effect for their
+//! collection when they are created, so a change only affects sales created
+//! after it takes effect.
+
+use soroban_sdk::Env;
+use crate::{ScheduledFeeChange, errors::Error};
+
+/// Minimum time between scheduling a change and it taking effect
+pub const FEE_CHANGE_NOTICE: u64 = 7 * 24 * 60 * 60;
+
+/// Minimum time between two scheduled changes taking effect
+pub const FEE_CHANGE_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+/// Most a single scheduled change can move the fee, in bps
+pub const MAX_FEE_CHANGE_BPS: u32 = 100;
+
+/// Validate a change against the fee in effect and the last change
+/// Fails with `InvalidFee` if the fee is out of range or moves too far, and
+/// `InvalidExpiry` if the notice or period is too short.
+pub fn validate_change(
+    env: &Env,
+    current_fee_bps: u32,
+    last_change_at: Option<u64>,
+    change: &ScheduledFeeChange,
+) -> Result<(), Error> {
+    if change.fee_bps > 1000 || change.fee_bps.abs_diff(current_fee_bps) > MAX_FEE_CHANGE_BPS {
+        return Err(Error::InvalidFee);
+    }
+
+    if change.effective_at < env.ledger().timestamp() + FEE_CHANGE_NOTICE {
+        return Err(Error::InvalidExpiry);
+    }
+
+    if let Some(last_change_at) = last_change_at {
+        if change.effective_at < last_change_at + FEE_CHANGE_PERIOD {
+            return Err(Error::InvalidExpiry);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether a scheduled change has taken effect
+pub fn is_due(env: &Env, change: &ScheduledFeeChange) -> bool {
+    env.ledger().timestamp() >= change.effective_at
+}
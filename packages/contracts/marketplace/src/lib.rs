@@ -5,18 +5,27 @@
 //! Production-ready marketplace for buying and selling NFTs on Stellar Soroban.
 //! Supports fixed-price listings, offers, and automatic royalty distribution.
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, xdr::FromXdr, Address, Bytes, BytesN, Env, Map, Symbol, Vec};
 
 mod storage;
 mod events;
 mod errors;
 mod royalty;
+mod orders;
+mod auctions;
+mod raffles;
+mod fee_schedule;
 
 use storage::*;
-use events::*;
 use errors::Error;
+use nuna_governance as governance;
+
+pub use nuna_governance::{GovernanceConfig, ProposalStatus};
 use royalty::*;
 
+/// Acknowledgement `on_nft_received` returns to accept a safe transfer
+pub const NFT_RECEIVED: Symbol = symbol_short!("nft_rcvd");
+
 #[contract]
 pub struct MarketplaceContract;
 
@@ -27,6 +36,8 @@ pub enum ListingStatus {
     Sold,
     Cancelled,
     Expired,
+    /// Token left the seller (transfer/burn) or approval was revoked
+    Invalidated,
 }
 
 #[contracttype]
@@ -40,6 +51,22 @@ pub struct Listing {
     pub status: ListingStatus,
     pub created_at: u64,
     pub expires_at: Option<u64>,
+    /// Private (OTC) listing: only this address may buy
+    pub reserved_buyer: Option<Address>,
+    /// Bundle listing: every (nft_contract, token_id) sold together.
+    /// Empty for single-token listings.
+    pub bundle_items: Vec<(Address, u64)>,
+    /// Platform fee per listed token (collection overrides included),
+    /// locked when the listing was created
+    pub platform_fee_bps: Vec<u32>,
+}
+
+/// One entry in a listing's price history
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PricePoint {
+    pub price: i128,
+    pub timestamp: u64,
 }
 
 #[contracttype]
@@ -54,6 +81,358 @@ pub struct Offer {
     pub created_at: u64,
 }
 
+/// Seller's counter to an offer; the offer's buyer may accept it
+/// Every counter to the same offer shares its thread ID.
+#[contracttype]
+#[derive(Clone)]
+pub struct CounterOffer {
+    pub counter_id: u64,
+    /// ID of the offer that started the negotiation
+    pub thread_id: u64,
+    pub nft_contract: Address,
+    pub token_id: u64,
+    pub seller: Address,
+    pub buyer: Address,
+    pub amount: i128,
+    pub expires_at: Option<u64>,
+    pub created_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SwapStatus {
+    Open,
+    Accepted,
+    Cancelled,
+}
+
+/// Peer-to-peer NFT swap proposal
+/// The maker's optional payment is escrowed by the marketplace until the
+/// taker accepts or the maker cancels
+#[contracttype]
+#[derive(Clone)]
+pub struct Swap {
+    pub swap_id: u64,
+    pub maker: Address,
+    pub maker_items: Vec<(Address, u64)>,
+    /// Payment token top-up from maker to taker (0 for a pure swap)
+    pub payment_amount: i128,
+    pub taker: Address,
+    pub taker_items: Vec<(Address, u64)>,
+    pub status: SwapStatus,
+    pub created_at: u64,
+    pub expires_at: Option<u64>,
+}
+
+/// Off-chain signed sell order (Seaport-style)
+/// The seller signs `get_order_hash(order)`; nothing is stored until it is
+/// filled or cancelled
+#[contracttype]
+#[derive(Clone)]
+pub struct Order {
+    pub seller: Address,
+    pub nft_contract: Address,
+    pub token_id: u64,
+    pub price: i128,
+    pub payment_token: Address,
+    pub expires_at: u64,
+    pub salt: u64,
+    pub nonce: u64,
+}
+
+/// Outcome of a single listing within `batch_buy`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BatchBuyStatus {
+    Purchased,
+    NotFound,
+    NotActive,
+    Expired,
+    OwnListing,
+    NotReservedBuyer,
+    OverBudget,
+    /// The seller no longer owns the token or has revoked approval
+    Unavailable,
+    /// The collection is blocked or not allowed for trading
+    Blocked,
+}
+
+impl BatchBuyStatus {
+    fn from_error(err: Error) -> Self {
+        match err {
+            Error::ListingNotFound => BatchBuyStatus::NotFound,
+            Error::ListingExpired => BatchBuyStatus::Expired,
+            Error::CannotBuyOwnListing => BatchBuyStatus::OwnListing,
+            Error::NotReservedBuyer => BatchBuyStatus::NotReservedBuyer,
+            Error::MaxTotalExceeded => BatchBuyStatus::OverBudget,
+            Error::NotTokenOwner => BatchBuyStatus::Unavailable,
+            Error::CollectionBlocked | Error::CollectionNotAllowed => BatchBuyStatus::Blocked,
+            _ => BatchBuyStatus::NotActive,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchBuyResult {
+    pub listing_id: u64,
+    pub status: BatchBuyStatus,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OrderState {
+    Filled,
+    Cancelled,
+}
+
+/// Registry status of an NFT collection
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CollectionStatus {
+    /// Reviewed and badged as authentic
+    Verified,
+    /// Tradable while the marketplace is allowlist-only
+    Allowed,
+    /// Cannot be listed, offered on or sold
+    Blocked,
+}
+
+/// Which collections can be traded
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TradingMode {
+    /// Any collection that isn't blocked (default)
+    Open,
+    /// Only verified or allowed collections
+    AllowlistOnly,
+}
+
+/// Seller volume tier: sellers with at least `min_sales` completed sales
+/// pay at most `fee_bps`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeTier {
+    pub min_sales: u32,
+    pub fee_bps: u32,
+}
+
+/// How a sale price is divided between platform, royalty recipients and seller
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeQuote {
+    pub platform_fee: i128,
+    /// Registered referrer receiving part of the platform fee, if any
+    pub referrer: Option<Address>,
+    /// Portion of `platform_fee` paid to the referrer
+    pub referral_fee: i128,
+    /// (recipient, amount) per item with a non-zero royalty
+    pub royalties: Vec<(Address, i128)>,
+    pub royalty_amount: i128,
+    pub seller_proceeds: i128,
+}
+
+/// What a buy or offer acceptance would pay out right now
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SalePreview {
+    pub price: i128,
+    pub payment_token: Address,
+    /// Platform fee, each royalty payment and seller proceeds
+    pub fees: FeeQuote,
+}
+
+/// How platform fees and royalties reach a recipient
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PayoutMode {
+    /// Transferred during the sale (default)
+    Push,
+    /// Held by the marketplace until the recipient calls `withdraw`
+    Pull,
+}
+
+/// How the winner of a sealed-bid auction is charged
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuctionPricing {
+    /// Winner pays their own bid
+    FirstPrice,
+    /// Winner pays the second-highest valid bid (at least the reserve)
+    SecondPrice,
+}
+
+/// What happens to the deposit of a bid that was never revealed
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnrevealedPolicy {
+    /// Paid to the seller once the auction settles
+    Forfeit,
+    /// Returned to the bidder
+    Refund,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuctionStatus {
+    Open,
+    Settled,
+    /// Cancelled before any bids, or the token could not be delivered
+    Cancelled,
+}
+
+/// Seller-chosen parameters of a sealed-bid auction
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SealedAuctionTerms {
+    pub reserve_price: i128,
+    /// Escrowed with every commitment
+    pub deposit: i128,
+    pub pricing: AuctionPricing,
+    pub unrevealed: UnrevealedPolicy,
+    /// Commitments accepted until this timestamp (inclusive)
+    pub bid_end: u64,
+    /// Reveals accepted after `bid_end` until this timestamp (inclusive)
+    pub reveal_end: u64,
+}
+
+/// Sealed-bid (commit-reveal) auction for a single token
+#[contracttype]
+#[derive(Clone)]
+pub struct SealedAuction {
+    pub auction_id: u64,
+    pub nft_contract: Address,
+    pub token_id: u64,
+    pub seller: Address,
+    pub terms: SealedAuctionTerms,
+    pub status: AuctionStatus,
+    pub bid_count: u32,
+    /// Highest revealed bid at or above the reserve
+    pub highest_bidder: Option<Address>,
+    pub highest_bid: i128,
+    pub second_bid: i128,
+    pub created_at: u64,
+    /// Platform fee (collection override included) locked when the auction
+    /// was created
+    pub platform_fee_bps: u32,
+}
+
+/// A bidder's commitment and escrowed funds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SealedBid {
+    pub commitment: BytesN<32>,
+    /// Deposit, topped up to the bid amount on reveal
+    pub escrowed: i128,
+    pub revealed_amount: Option<i128>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RaffleStatus {
+    Open,
+    /// Winner drawn and proceeds paid out
+    Drawn,
+    /// Minimum not met (or the prize couldn't be delivered); tickets refunded
+    Cancelled,
+}
+
+/// Seller-chosen parameters of a raffle
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RaffleTerms {
+    pub ticket_price: i128,
+    /// Ticket cap; the raffle can be drawn early once it sells out
+    pub max_tickets: u32,
+    /// Tickets that must be sold for the draw to happen
+    pub min_tickets: u32,
+    /// Tickets sold until this timestamp (inclusive)
+    pub end_time: u64,
+}
+
+/// Raffle for a single escrowed token
+#[contracttype]
+#[derive(Clone)]
+pub struct Raffle {
+    pub raffle_id: u64,
+    pub nft_contract: Address,
+    pub token_id: u64,
+    pub seller: Address,
+    pub terms: RaffleTerms,
+    pub status: RaffleStatus,
+    pub tickets_sold: u32,
+    pub winner: Option<Address>,
+    pub created_at: u64,
+    /// Platform fee (collection override included) locked when the raffle
+    /// was created
+    pub platform_fee_bps: u32,
+}
+
+/// `data` for a safe transfer to the marketplace: starts a raffle for the
+/// received token, with the sender as seller
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RaffleDeposit {
+    /// The NFT contract making the transfer
+    pub nft_contract: Address,
+    pub terms: RaffleTerms,
+}
+
+/// Platform fee change announced ahead of time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledFeeChange {
+    pub fee_bps: u32,
+    pub effective_at: u64,
+}
+
+/// Admin operation that can be applied directly or through a proposal
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    /// Lower the platform fee immediately; increases must be scheduled
+    SetPlatformFee(u32),
+    /// (new fee bps, effective at)
+    ScheduleFeeChange(u32, u64),
+    CancelFeeChange,
+    SetMaxRoyalty(u32),
+    SetFeeRecipient(Address),
+    SetCollectionStatus(Address, CollectionStatus),
+    /// Remove a collection from the registry
+    ClearCollectionStatus(Address),
+    /// Lower a collection's fee immediately; increases must be scheduled
+    SetCollectionFee(Address, Option<u32>),
+    /// (collection, new fee bps, effective at)
+    ScheduleCollectionFeeChange(Address, u32, u64),
+    CancelCollectionFeeChange(Address),
+    SetTradingMode(TradingMode),
+    SetFeeTiers(Vec<FeeTier>),
+    /// (membership NFT contract, discount bps)
+    SetMembershipDiscount(Address, u32),
+    RemoveMembershipDiscount,
+    SetReferralShare(u32),
+    RegisterReferrer(Address, Option<u32>),
+    RemoveReferrer(Address),
+    SetPayoutMode(Address, PayoutMode),
+    /// Change signers, threshold or delay
+    SetGovernance(GovernanceConfig),
+    /// Return control to the admin key
+    DisableGovernance,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposal_id: u64,
+    pub action: AdminAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+    pub status: ProposalStatus,
+    pub created_at: u64,
+    /// Set when the threshold is reached: reached time + delay
+    pub executable_at: Option<u64>,
+}
+
 #[contractimpl]
 impl MarketplaceContract {
     /// Initialize the marketplace
@@ -91,6 +470,9 @@ impl MarketplaceContract {
     }
 
     /// Create a fixed-price listing
+    /// reserved_buyer: if set, only this address can buy (private/OTC listing)
+    /// Fails with `ListingStillValid` if the token already has a listing
+    /// that can still be filled.
     pub fn create_listing(
         env: Env,
         nft_contract: Address,
@@ -98,6 +480,7 @@ impl MarketplaceContract {
         seller: Address,
         price: i128,
         expires_at: Option<u64>,
+        reserved_buyer: Option<Address>,
     ) -> Result<u64, Error> {
         seller.require_auth();
 
@@ -114,6 +497,14 @@ impl MarketplaceContract {
             }
         }
 
+        // Seller cannot reserve the listing for themselves
+        if reserved_buyer == Some(seller.clone()) {
+            return Err(Error::CannotBuyOwnListing);
+        }
+
+        check_collection_tradable(&env, &nft_contract)?;
+        check_not_listed(&env, &nft_contract, token_id)?;
+
         // Generate listing ID
         let listing_id = get_and_increment_listing_counter(&env);
 
@@ -127,10 +518,14 @@ impl MarketplaceContract {
             status: ListingStatus::Active,
             created_at: env.ledger().timestamp(),
             expires_at,
+            reserved_buyer: reserved_buyer.clone(),
+            bundle_items: Vec::new(&env),
+            platform_fee_bps: Vec::from_array(&env, [collection_fee_bps(&env, &nft_contract)]),
         };
 
         save_listing(&env, listing_id, &listing);
-        add_active_listing(&env, listing_id);
+        add_active_listing(&env, &listing);
+        add_price_point(&env, listing_id, price);
 
         events::emit_listing_created(
             &env,
@@ -139,145 +534,309 @@ impl MarketplaceContract {
             token_id,
             &seller,
             price,
+            &reserved_buyer,
         );
 
         Ok(listing_id)
     }
 
-    /// Cancel a listing
-    pub fn cancel_listing(
+    /// Create a bundle listing: several NFTs sold together at one price
+    /// Items may come from different collections; the marketplace must be
+    /// approved for each one. Max 50 items per bundle.
+    pub fn create_bundle_listing(
         env: Env,
-        listing_id: u64,
         seller: Address,
-    ) -> Result<(), Error> {
+        items: Vec<(Address, u64)>,
+        price: i128,
+        expires_at: Option<u64>,
+        reserved_buyer: Option<Address>,
+    ) -> Result<u64, Error> {
         seller.require_auth();
 
-        let mut listing = get_listing(&env, listing_id)?;
+        // Validate bundle size
+        if items.len() < 2 {
+            return Err(Error::InvalidBundle);
+        }
+        if items.len() > 50 {
+            return Err(Error::BatchTooLarge);
+        }
 
-        // Verify seller
-        if listing.seller != seller {
-            return Err(Error::Unauthorized);
+        // Validate price
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
         }
 
-        // Check if already inactive
-        if listing.status != ListingStatus::Active {
-            return Err(Error::ListingNotActive);
+        // Validate expiry
+        if let Some(expiry) = expires_at {
+            if expiry <= env.ledger().timestamp() {
+                return Err(Error::InvalidExpiry);
+            }
         }
 
-        // Update listing
-        listing.status = ListingStatus::Cancelled;
+        // Seller cannot reserve the listing for themselves
+        if reserved_buyer == Some(seller.clone()) {
+            return Err(Error::CannotBuyOwnListing);
+        }
+
+        if has_duplicate_items(&items) {
+            return Err(Error::InvalidBundle);
+        }
+
+        // Verify ownership and approval for every item
+        for (nft_contract, token_id) in items.iter() {
+            check_collection_tradable(&env, &nft_contract)?;
+            check_owner_and_approval(&env, &nft_contract, token_id, &seller)?;
+            check_not_listed(&env, &nft_contract, token_id)?;
+        }
+
+        // Generate listing ID
+        let listing_id = get_and_increment_listing_counter(&env);
+        let (nft_contract, token_id) = items.get(0).unwrap();
+
+        let listing = Listing {
+            listing_id,
+            nft_contract,
+            token_id,
+            seller: seller.clone(),
+            price,
+            status: ListingStatus::Active,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+            reserved_buyer: reserved_buyer.clone(),
+            platform_fee_bps: current_fees(&env, &items),
+            bundle_items: items.clone(),
+        };
+
         save_listing(&env, listing_id, &listing);
-        remove_active_listing(&env, listing_id);
+        add_active_listing(&env, &listing);
+        add_price_point(&env, listing_id, price);
 
-        events::emit_listing_cancelled(&env, listing_id, &seller);
+        events::emit_bundle_listing_created(
+            &env,
+            listing_id,
+            &items,
+            &seller,
+            price,
+            &reserved_buyer,
+        );
 
-        Ok(())
+        Ok(listing_id)
     }
 
-    /// Buy NFT from listing
-    pub fn buy(
+    /// Update price and expiry of an active listing in place
+    /// Keeps the listing ID and records the change in the price history
+    pub fn update_listing(
         env: Env,
         listing_id: u64,
-        buyer: Address,
+        seller: Address,
+        new_price: i128,
+        new_expires_at: Option<u64>,
     ) -> Result<(), Error> {
-        buyer.require_auth();
+        seller.require_auth();
 
         let mut listing = get_listing(&env, listing_id)?;
 
-        // Verify listing is active
+        // Verify seller
+        if listing.seller != seller {
+            return Err(Error::Unauthorized);
+        }
+
+        // Only active, unexpired listings can be updated
         if listing.status != ListingStatus::Active {
             return Err(Error::ListingNotActive);
         }
-
-        // Check expiry
         if let Some(expiry) = listing.expires_at {
             if env.ledger().timestamp() > expiry {
-                listing.status = ListingStatus::Expired;
-                save_listing(&env, listing_id, &listing);
-                remove_active_listing(&env, listing_id);
                 return Err(Error::ListingExpired);
             }
         }
 
-        // Cannot buy your own listing
-        if listing.seller == buyer {
-            return Err(Error::CannotBuyOwnListing);
+        // Validate price
+        if new_price <= 0 {
+            return Err(Error::InvalidPrice);
         }
 
-        // Calculate fees
-        let platform_fee_bps = get_platform_fee(&env);
-        let platform_fee = calculate_fee(listing.price, platform_fee_bps);
-
-        // Get royalty info (if available)
-        let (royalty_recipient, royalty_amount) = get_royalty_info(
-            &env,
-            &listing.nft_contract,
-            listing.token_id,
-            listing.price,
-        );
-
-        // Calculate seller proceeds
-        let mut seller_proceeds = listing.price - platform_fee;
-        if royalty_amount > 0 {
-            seller_proceeds -= royalty_amount;
+        // Validate expiry
+        if let Some(expiry) = new_expires_at {
+            if expiry <= env.ledger().timestamp() {
+                return Err(Error::InvalidExpiry);
+            }
         }
 
-        // Transfer payments
-        transfer_xlm(&env, &buyer, &listing.seller, seller_proceeds)?;
-
-        if platform_fee > 0 {
-            let fee_recipient = get_fee_recipient(&env)?;
-            transfer_xlm(&env, &buyer, &fee_recipient, platform_fee)?;
-        }
+        let old_price = listing.price;
+        listing.price = new_price;
+        listing.expires_at = new_expires_at;
+        save_listing(&env, listing_id, &listing);
 
-        if royalty_amount > 0 {
-            transfer_xlm(&env, &buyer, &royalty_recipient, royalty_amount)?;
+        if new_price != old_price {
+            add_price_point(&env, listing_id, new_price);
         }
 
-        // Transfer NFT (call transfer_from on NFT contract)
-        transfer_nft(
-            &env,
-            &listing.nft_contract,
-            &listing.seller,
-            &buyer,
-            listing.token_id,
-        )?;
-
-        // Update listing status
-        listing.status = ListingStatus::Sold;
-        save_listing(&env, listing_id, &listing);
-        remove_active_listing(&env, listing_id);
-
-        events::emit_sale(
+        events::emit_listing_updated(
             &env,
             listing_id,
-            &listing.nft_contract,
-            listing.token_id,
-            &listing.seller,
-            &buyer,
-            listing.price,
-            platform_fee,
-            royalty_amount,
+            &seller,
+            old_price,
+            new_price,
+            new_expires_at,
         );
 
         Ok(())
     }
 
-    /// Make an offer on an NFT
-    pub fn make_offer(
+    /// Cancel a listing
+    pub fn cancel_listing(
         env: Env,
-        nft_contract: Address,
-        token_id: u64,
-        buyer: Address,
-        amount: i128,
-        expires_at: Option<u64>,
-    ) -> Result<u64, Error> {
-        buyer.require_auth();
+        listing_id: u64,
+        seller: Address,
+    ) -> Result<(), Error> {
+        seller.require_auth();
 
-        // Validate amount
-        if amount <= 0 {
-            return Err(Error::InvalidPrice);
-        }
+        let mut listing = get_listing(&env, listing_id)?;
+
+        // Verify seller
+        if listing.seller != seller {
+            return Err(Error::Unauthorized);
+        }
+
+        // Check if already inactive
+        if listing.status != ListingStatus::Active {
+            return Err(Error::ListingNotActive);
+        }
+
+        // Update listing
+        listing.status = ListingStatus::Cancelled;
+        save_listing(&env, listing_id, &listing);
+        remove_active_listing(&env, &listing);
+
+        events::emit_listing_cancelled(&env, listing_id, &seller);
+
+        Ok(())
+    }
+
+    /// Buy NFT from listing
+    /// referrer: registered partner that receives a share of the platform fee
+    pub fn buy(
+        env: Env,
+        listing_id: u64,
+        buyer: Address,
+        referrer: Option<Address>,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+
+        let mut listing = get_listing(&env, listing_id)?;
+
+        // Expired listings stay indexed until `expire_listings` prunes them
+        validate_purchase(&env, &listing, &buyer)?;
+
+        // Mark sold before the NFT(s) move, so the transfer doesn't
+        // invalidate this listing
+        listing.status = ListingStatus::Sold;
+        save_listing(&env, listing_id, &listing);
+        remove_active_listing(&env, &listing);
+
+        // Collect payment, split fees and royalties, move the NFT(s)
+        let split = settle_sale(
+            &env,
+            &Sale {
+                payment_token: get_xlm_token_address(&env),
+                payer: buyer.clone(),
+                items: listed_tokens(&env, &listing),
+                seller: listing.seller.clone(),
+                price: listing.price,
+                platform_fee_bps: listing.platform_fee_bps.clone(),
+            },
+            &buyer,
+            &referrer,
+        )?;
+
+        events::emit_sale(&env, &listing, &buyer, &split);
+
+        Ok(())
+    }
+
+    /// Buy several listings in one transaction (floor sweep)
+    ///
+    /// max_total: upper bound on the summed price of purchased listings
+    /// allow_partial: skip listings that can't be bought instead of failing
+    /// referrer: registered partner that receives a share of the platform fee
+    ///
+    /// Payments are aggregated per recipient so each seller, royalty
+    /// recipient and the fee recipient receive a single token transfer
+    /// (or a single ledger credit for pull-mode recipients).
+    /// Max 100 listings per batch.
+    pub fn batch_buy(
+        env: Env,
+        listing_ids: Vec<u64>,
+        buyer: Address,
+        max_total: i128,
+        allow_partial: bool,
+        referrer: Option<Address>,
+    ) -> Result<Vec<BatchBuyResult>, Error> {
+        buyer.require_auth();
+
+        // Validate batch size
+        let count = listing_ids.len();
+        if count == 0 {
+            return Err(Error::BatchEmpty);
+        }
+        if count > 100 {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let payment_token = get_xlm_token_address(&env);
+        let fee_recipient = get_fee_recipient(&env)?;
+
+        let mut payouts = Payouts::new(&env);
+        let mut results = Vec::new(&env);
+        let mut total_spent: i128 = 0;
+        let mut purchased: u32 = 0;
+
+        for listing_id in listing_ids.iter() {
+            let budget = max_total - total_spent;
+
+            match buy_in_batch(&env, listing_id, &buyer, &referrer, &fee_recipient, budget, &mut payouts) {
+                Ok(price) => {
+                    total_spent += price;
+                    purchased += 1;
+                    results.push_back(BatchBuyResult {
+                        listing_id,
+                        status: BatchBuyStatus::Purchased,
+                    });
+                }
+                // A failed transfer may follow other items of the same bundle
+                // already moving, so it aborts the batch instead of being skipped
+                Err(err) if allow_partial && err != Error::TransferFailed => results.push_back(BatchBuyResult {
+                    listing_id,
+                    status: BatchBuyStatus::from_error(err),
+                }),
+                Err(err) => return Err(err),
+            }
+        }
+
+        // One transfer (or ledger credit) per recipient
+        payouts.pay(&env, &payment_token, &buyer)?;
+
+        events::emit_batch_buy(&env, &buyer, purchased, total_spent);
+
+        Ok(results)
+    }
+
+    /// Make an offer on an NFT
+    pub fn make_offer(
+        env: Env,
+        nft_contract: Address,
+        token_id: u64,
+        buyer: Address,
+        amount: i128,
+        expires_at: Option<u64>,
+    ) -> Result<u64, Error> {
+        buyer.require_auth();
+
+        // Validate amount
+        if amount <= 0 {
+            return Err(Error::InvalidPrice);
+        }
 
         // Validate expiry
         if let Some(expiry) = expires_at {
@@ -287,6 +846,8 @@ impl MarketplaceContract {
             }
         }
 
+        check_collection_tradable(&env, &nft_contract)?;
+
         // Generate offer ID
         let offer_id = get_and_increment_offer_counter(&env);
 
@@ -302,6 +863,7 @@ impl MarketplaceContract {
         };
 
         save_offer(&env, offer_id, &offer);
+        add_open_offer(&env, &offer);
 
         events::emit_offer_made(
             &env,
@@ -316,10 +878,12 @@ impl MarketplaceContract {
     }
 
     /// Accept an offer
+    /// referrer: registered partner that receives a share of the platform fee
     pub fn accept_offer(
         env: Env,
         offer_id: u64,
         seller: Address,
+        referrer: Option<Address>,
     ) -> Result<(), Error> {
         seller.require_auth();
 
@@ -328,60 +892,49 @@ impl MarketplaceContract {
         // Check expiry
         if let Some(expiry) = offer.expires_at {
             if env.ledger().timestamp() > expiry {
-                remove_offer(&env, offer_id);
+                close_offer(&env, &offer)?;
                 return Err(Error::OfferExpired);
             }
         }
 
-        // Calculate fees (similar to buy)
-        let platform_fee_bps = get_platform_fee(&env);
-        let platform_fee = calculate_fee(offer.amount, platform_fee_bps);
-
-        let (royalty_recipient, royalty_amount) = get_royalty_info(
-            &env,
-            &offer.nft_contract,
-            offer.token_id,
-            offer.amount,
-        );
-
-        let mut seller_proceeds = offer.amount - platform_fee;
-        if royalty_amount > 0 {
-            seller_proceeds -= royalty_amount;
-        }
-
-        // Transfer payments
-        transfer_xlm(&env, &offer.buyer, &seller, seller_proceeds)?;
-
-        if platform_fee > 0 {
-            let fee_recipient = get_fee_recipient(&env)?;
-            transfer_xlm(&env, &offer.buyer, &fee_recipient, platform_fee)?;
-        }
-
-        if royalty_amount > 0 {
-            transfer_xlm(&env, &offer.buyer, &royalty_recipient, royalty_amount)?;
-        }
+        // Part of an offer raised by an accepted counter is already held by
+        // the marketplace; collect the rest and pay the sale from escrow
+        let payment_token = get_xlm_token_address(&env);
+        let escrowed = get_offer_escrow(&env, offer_id);
+        let payer = if escrowed > 0 {
+            set_offer_escrow(&env, offer_id, 0);
+            transfer_payment(
+                &env,
+                &payment_token,
+                &offer.buyer,
+                &env.current_contract_address(),
+                offer.amount - escrowed,
+            )?;
+            env.current_contract_address()
+        } else {
+            offer.buyer.clone()
+        };
 
-        // Transfer NFT
-        transfer_nft(
+        // Collect payment, split fees and royalties, move the NFT
+        let items = Vec::from_array(&env, [(offer.nft_contract.clone(), offer.token_id)]);
+        let split = settle_sale(
             &env,
-            &offer.nft_contract,
-            &seller,
+            &Sale {
+                payment_token,
+                payer,
+                platform_fee_bps: current_fees(&env, &items),
+                items,
+                seller: seller.clone(),
+                price: offer.amount,
+            },
             &offer.buyer,
-            offer.token_id,
+            &referrer,
         )?;
 
-        // Remove offer
-        remove_offer(&env, offer_id);
+        // Remove offer and any counters to it
+        close_offer(&env, &offer)?;
 
-        events::emit_offer_accepted(
-            &env,
-            offer_id,
-            &offer.nft_contract,
-            offer.token_id,
-            &seller,
-            &offer.buyer,
-            offer.amount,
-        );
+        events::emit_offer_accepted(&env, &offer, &seller, &split);
 
         Ok(())
     }
@@ -400,144 +953,2597 @@ impl MarketplaceContract {
             return Err(Error::Unauthorized);
         }
 
-        remove_offer(&env, offer_id);
+        close_offer(&env, &offer)?;
 
         events::emit_offer_cancelled(&env, offer_id, &buyer);
 
         Ok(())
     }
 
-    // ========== VIEW FUNCTIONS ==========
+    // ========== COUNTER-OFFERS ==========
 
-    pub fn get_listing(env: Env, listing_id: u64) -> Result<Listing, Error> {
-        get_listing(&env, listing_id)
-    }
+    /// Counter an offer with a higher amount
+    /// The offer stays open; the buyer may accept the counter, raising the
+    /// offer to `new_amount`. Returns the counter ID.
+    pub fn counter_offer(
+        env: Env,
+        offer_id: u64,
+        seller: Address,
+        new_amount: i128,
+        expires_at: Option<u64>,
+    ) -> Result<u64, Error> {
+        seller.require_auth();
 
-    pub fn get_offer(env: Env, offer_id: u64) -> Result<Offer, Error> {
-        get_offer(&env, offer_id)
-    }
+        let offer = get_offer(&env, offer_id)?;
+        let now = env.ledger().timestamp();
 
-    pub fn get_platform_fee(env: Env) -> u32 {
-        get_platform_fee(&env)
-    }
+        if offer.buyer == seller {
+            return Err(Error::CannotBuyOwnListing);
+        }
 
-    pub fn get_admin(env: Env) -> Result<Address, Error> {
-        get_admin(&env)
-    }
+        if offer.expires_at.is_some_and(|expiry| now > expiry) {
+            return Err(Error::OfferExpired);
+        }
 
-    // ========== ADMIN FUNCTIONS ==========
+        // A counter at or below the offer should just accept it
+        if new_amount <= offer.amount {
+            return Err(Error::InvalidPrice);
+        }
 
-    pub fn set_platform_fee(
+        if let Some(expiry) = expires_at {
+            if expiry <= now {
+                return Err(Error::InvalidExpiry);
+            }
+        }
+
+        check_owner_and_approval(&env, &offer.nft_contract, offer.token_id, &seller)?;
+
+        let counter_id = get_and_increment_counter_offer_counter(&env);
+        let counter = CounterOffer {
+            counter_id,
+            thread_id: offer.offer_id,
+            nft_contract: offer.nft_contract,
+            token_id: offer.token_id,
+            seller: seller.clone(),
+            buyer: offer.buyer.clone(),
+            amount: new_amount,
+            expires_at,
+            created_at: now,
+        };
+
+        save_counter_offer(&env, &counter);
+        add_to_offer_thread(&env, counter.thread_id, counter_id);
+
+        events::emit_counter_offer_made(
+            &env,
+            counter_id,
+            counter.thread_id,
+            &seller,
+            &offer.buyer,
+            new_amount,
+        );
+
+        Ok(counter_id)
+    }
+
+    /// Accept a counter-offer as the original buyer
+    /// Escrows the difference between the counter and the offer, raises the
+    /// offer to the countered amount and closes the other counters. The
+    /// seller then settles with `accept_offer`; the buyer can still cancel
+    /// the offer, which returns the escrow.
+    pub fn accept_counter_offer(
         env: Env,
-        admin: Address,
-        new_fee_bps: u32,
+        counter_id: u64,
+        buyer: Address,
     ) -> Result<(), Error> {
-        admin.require_auth();
+        buyer.require_auth();
+
+        let counter = get_counter_offer(&env, counter_id)?;
 
-        let current_admin = get_admin(&env)?;
-        if current_admin != admin {
+        if counter.buyer != buyer {
             return Err(Error::Unauthorized);
         }
 
-        if new_fee_bps > 1000 {
-            return Err(Error::InvalidFee);
+        if let Some(expiry) = counter.expires_at {
+            if env.ledger().timestamp() > expiry {
+                return Err(Error::OfferExpired);
+            }
+        }
+
+        let mut offer = get_offer(&env, counter.thread_id)?;
+
+        // The offer may already have been raised by another counter
+        let difference = counter.amount - offer.amount;
+        if difference <= 0 {
+            return Err(Error::InvalidPrice);
         }
 
-        set_platform_fee(&env, new_fee_bps);
+        transfer_payment(
+            &env,
+            &get_xlm_token_address(&env),
+            &buyer,
+            &env.current_contract_address(),
+            difference,
+        )?;
+        let escrowed = get_offer_escrow(&env, offer.offer_id) + difference;
+        set_offer_escrow(&env, offer.offer_id, escrowed);
+
+        offer.amount = counter.amount;
+        save_offer(&env, offer.offer_id, &offer);
+        remove_offer_thread(&env, offer.offer_id);
 
-        events::emit_fee_updated(&env, new_fee_bps);
+        events::emit_counter_offer_accepted(&env, &counter, escrowed);
 
         Ok(())
     }
 
-    pub fn set_fee_recipient(
+    /// Withdraw a counter-offer (seller) or decline it (buyer)
+    pub fn cancel_counter_offer(
         env: Env,
-        admin: Address,
-        new_recipient: Address,
+        counter_id: u64,
+        caller: Address,
     ) -> Result<(), Error> {
-        admin.require_auth();
+        caller.require_auth();
 
-        let current_admin = get_admin(&env)?;
-        if current_admin != admin {
+        let counter = get_counter_offer(&env, counter_id)?;
+
+        if caller != counter.seller && caller != counter.buyer {
             return Err(Error::Unauthorized);
         }
 
-        set_fee_recipient(&env, &new_recipient);
+        remove_counter_offer(&env, counter_id);
+        remove_from_offer_thread(&env, counter.thread_id, counter_id);
+
+        events::emit_counter_offer_cancelled(&env, counter_id, counter.thread_id, &caller);
 
         Ok(())
     }
-}
 
-/// Calculate fee based on basis points
-fn calculate_fee(amount: i128, fee_bps: u32) -> i128 {
-    (amount * fee_bps as i128) / 10000
-}
+    // ========== LISTING INVALIDATION ==========
 
-/// Transfer XLM between accounts using Stellar Asset Contract
-fn transfer_xlm(
-    env: &Env,
-    from: &Address,
-    to: &Address,
-    amount: i128,
-) -> Result<(), Error> {
-    use soroban_sdk::token;
+    /// Notification from an NFT contract that a token was transferred or burned
+    /// Invalidates the token's active listing if it left the seller.
+    /// Only the NFT contract itself can call this (via `set_marketplace_hook`).
+    pub fn on_token_moved(
+        env: Env,
+        nft_contract: Address,
+        token_id: u64,
+        from: Address,
+        to: Option<Address>,
+    ) -> Result<(), Error> {
+        nft_contract.require_auth();
 
-    // Get the native XLM token contract address
-    // On Stellar mainnet/testnet, XLM is represented as a Stellar Asset Contract
-    let xlm_address = get_xlm_token_address(env);
+        if to != Some(from.clone()) {
+            invalidate_token_listing(&env, &nft_contract, token_id, &from);
+        }
 
-    // Create token client for XLM
-    let xlm_token = token::Client::new(env, &xlm_address);
+        Ok(())
+    }
 
-    // Transfer XLM from buyer to recipient
-    // This will fail if buyer doesn't have enough balance or hasn't approved
-    xlm_token.transfer(from, to, &amount);
+    /// Invalidate a listing whose seller no longer owns (or no longer
+    /// approved the marketplace for) a listed token. Callable by anyone,
+    /// for collections that don't notify the marketplace.
+    pub fn prune_invalid_listing(env: Env, listing_id: u64) -> Result<(), Error> {
+        let listing = get_listing(&env, listing_id)?;
 
-    Ok(())
-}
+        if listing.status != ListingStatus::Active {
+            return Err(Error::ListingNotActive);
+        }
 
-/// Transfer NFT via cross-contract call to NFT contract
-fn transfer_nft(
-    env: &Env,
-    nft_contract: &Address,
-    from: &Address,
-    to: &Address,
-    token_id: u64,
-) -> Result<(), Error> {
-    use nft_interface::NFTContractClient;
+        if is_listing_deliverable(&env, &listing) {
+            return Err(Error::ListingStillValid);
+        }
 
-    // Create a client for the NFT contract using its interface
-    // We'll call transfer_from as the marketplace (authorized operator)
-    let nft_client = NFTContractClient::new(env, nft_contract);
+        invalidate_listing(&env, listing);
+        Ok(())
+    }
 
-    // The marketplace contract must be approved to transfer this NFT
-    // This should have been done when the listing was created
-    nft_client.transfer_from(
-        &env.current_contract_address(), // spender (this marketplace)
-        from,                             // current owner
-        to,                               // new owner
-        &token_id,
-    );
+    // ========== NFT RECEIVER ==========
 
-    Ok(())
-}
+    /// Safe-transfer hook called by NFT contracts
+    /// The marketplace only keeps tokens it escrows, so a safe transfer is
+    /// accepted only as a raffle deposit: `data` is an XDR `RaffleDeposit`
+    /// and the owner must be sending their own token. Anything else is
+    /// rejected and the transfer reverts. Returns `NFT_RECEIVED`.
+    pub fn on_nft_received(
+        env: Env,
+        operator: Address,
+        from: Address,
+        token_id: u64,
+        data: Bytes,
+    ) -> Result<Symbol, Error> {
+        if operator != from {
+            return Err(Error::Unauthorized);
+        }
 
-/// Get XLM token address for the current network
-/// This is the Stellar Asset Contract (SAC) address for native XLM
-fn get_xlm_token_address(env: &Env) -> Address {
-    // On Stellar, native XLM has a deterministic contract address
-    // This is computed from the Stellar Asset (native)
-    // For now, we'll store it in contract storage during initialization
-    storage::get_xlm_token_address(env)
-        .expect("XLM token address not configured")
-}
+        let deposit = RaffleDeposit::from_xdr(&env, &data).map_err(|_| Error::InvalidRaffle)?;
 
-/// Client interface for calling NFT contract functions
-mod nft_interface {
-    use soroban_sdk::{contractclient, Address, Env};
+        // Only the NFT contract itself can call this during its transfer
+        deposit.nft_contract.require_auth();
+
+        raffles::validate_terms(&env, &deposit.terms)?;
+        check_collection_tradable(&env, &deposit.nft_contract)?;
+        invalidate_token_listing(&env, &deposit.nft_contract, token_id, &from);
+        start_raffle(&env, &from, &deposit.nft_contract, token_id, &deposit.terms)?;
+
+        Ok(NFT_RECEIVED)
+    }
+
+    // ========== EXPIRY CLEANUP ==========
+
+    /// Remove expired listings from the active indexes and delete their
+    /// storage (listing and price history). Callable by anyone.
+    /// IDs that are missing or not expired are skipped. Max 100 per call.
+    /// Returns the IDs that were removed.
+    pub fn expire_listings(env: Env, listing_ids: Vec<u64>) -> Result<Vec<u64>, Error> {
+        if listing_ids.is_empty() {
+            return Err(Error::BatchEmpty);
+        }
+        if listing_ids.len() > 100 {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut expired = Vec::new(&env);
+        for listing_id in listing_ids.iter() {
+            if let Ok(listing) = get_listing(&env, listing_id) {
+                if is_listing_expired(&env, &listing) {
+                    expire_listing(&env, listing);
+                    expired.push_back(listing_id);
+                }
+            }
+        }
+
+        Ok(expired)
+    }
+
+    /// Remove expired offers from the open-offer indexes and delete them.
+    /// Callable by anyone. IDs that are missing or not expired are skipped.
+    /// Max 100 per call. Returns the IDs that were removed.
+    pub fn expire_offers(env: Env, offer_ids: Vec<u64>) -> Result<Vec<u64>, Error> {
+        if offer_ids.is_empty() {
+            return Err(Error::BatchEmpty);
+        }
+        if offer_ids.len() > 100 {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let now = env.ledger().timestamp();
+        let mut expired = Vec::new(&env);
+        for offer_id in offer_ids.iter() {
+            if let Ok(offer) = get_offer(&env, offer_id) {
+                if offer.expires_at.is_some_and(|expiry| now > expiry) {
+                    close_offer(&env, &offer)?;
+                    events::emit_offer_expired(&env, offer_id, &offer.buyer);
+                    expired.push_back(offer_id);
+                }
+            }
+        }
+
+        Ok(expired)
+    }
+
+    // ========== SWAPS ==========
+
+    /// Propose swapping the maker's NFTs (plus optional payment) for the taker's NFTs
+    /// The payment amount is escrowed immediately. Max 50 items per side.
+    pub fn propose_swap(
+        env: Env,
+        maker: Address,
+        maker_items: Vec<(Address, u64)>,
+        payment_amount: i128,
+        taker: Address,
+        taker_items: Vec<(Address, u64)>,
+        expires_at: Option<u64>,
+    ) -> Result<u64, Error> {
+        maker.require_auth();
+
+        // Validate sides
+        if maker == taker || taker_items.is_empty() {
+            return Err(Error::InvalidSwap);
+        }
+        if maker_items.is_empty() && payment_amount == 0 {
+            return Err(Error::InvalidSwap);
+        }
+        if maker_items.len() > 50 || taker_items.len() > 50 {
+            return Err(Error::BatchTooLarge);
+        }
+        if has_duplicate_items(&maker_items) || has_duplicate_items(&taker_items) {
+            return Err(Error::InvalidSwap);
+        }
+        if payment_amount < 0 {
+            return Err(Error::InvalidPrice);
+        }
+
+        // Validate expiry
+        if let Some(expiry) = expires_at {
+            if expiry <= env.ledger().timestamp() {
+                return Err(Error::InvalidExpiry);
+            }
+        }
+
+        // Maker's items must be transferable by the marketplace now
+        for (nft_contract, token_id) in maker_items.iter() {
+            check_collection_tradable(&env, &nft_contract)?;
+            check_owner_and_approval(&env, &nft_contract, token_id, &maker)?;
+        }
+
+        // Taker must currently own the requested items
+        for (nft_contract, token_id) in taker_items.iter() {
+            check_collection_tradable(&env, &nft_contract)?;
+            check_owner(&env, &nft_contract, token_id, &taker)?;
+        }
+
+        // Escrow the maker's payment
+        if payment_amount > 0 {
+            transfer_payment(
+                &env,
+                &get_xlm_token_address(&env),
+                &maker,
+                &env.current_contract_address(),
+                payment_amount,
+            )?;
+        }
+
+        let swap_id = get_and_increment_swap_counter(&env);
+
+        let swap = Swap {
+            swap_id,
+            maker: maker.clone(),
+            maker_items,
+            payment_amount,
+            taker: taker.clone(),
+            taker_items,
+            status: SwapStatus::Open,
+            created_at: env.ledger().timestamp(),
+            expires_at,
+        };
+
+        save_swap(&env, swap_id, &swap);
+
+        events::emit_swap_proposed(&env, &swap);
+
+        Ok(swap_id)
+    }
+
+    /// Accept a swap: both sides' NFTs and the escrowed payment move atomically
+    pub fn accept_swap(
+        env: Env,
+        swap_id: u64,
+        taker: Address,
+    ) -> Result<(), Error> {
+        taker.require_auth();
+
+        let mut swap = get_swap(&env, swap_id)?;
+
+        if swap.taker != taker {
+            return Err(Error::Unauthorized);
+        }
+
+        if swap.status != SwapStatus::Open {
+            return Err(Error::SwapNotOpen);
+        }
+
+        // Check expiry
+        if let Some(expiry) = swap.expires_at {
+            if env.ledger().timestamp() > expiry {
+                return Err(Error::SwapExpired);
+            }
+        }
+
+        swap.status = SwapStatus::Accepted;
+        save_swap(&env, swap_id, &swap);
+
+        // Maker's NFTs to taker
+        for (nft_contract, token_id) in swap.maker_items.iter() {
+            transfer_nft(&env, &nft_contract, &swap.maker, &taker, token_id)?;
+        }
+
+        // Taker's NFTs to maker
+        for (nft_contract, token_id) in swap.taker_items.iter() {
+            transfer_nft(&env, &nft_contract, &taker, &swap.maker, token_id)?;
+        }
+
+        // Release escrowed payment to taker
+        if swap.payment_amount > 0 {
+            transfer_payment(
+                &env,
+                &get_xlm_token_address(&env),
+                &env.current_contract_address(),
+                &taker,
+                swap.payment_amount,
+            )?;
+        }
+
+        events::emit_swap_accepted(&env, swap_id, &swap.maker, &taker);
+
+        Ok(())
+    }
+
+    /// Cancel an open swap and refund the escrowed payment
+    /// Expired swaps are cancelled the same way
+    pub fn cancel_swap(
+        env: Env,
+        swap_id: u64,
+        maker: Address,
+    ) -> Result<(), Error> {
+        maker.require_auth();
+
+        let mut swap = get_swap(&env, swap_id)?;
+
+        if swap.maker != maker {
+            return Err(Error::Unauthorized);
+        }
+
+        if swap.status != SwapStatus::Open {
+            return Err(Error::SwapNotOpen);
+        }
+
+        swap.status = SwapStatus::Cancelled;
+        save_swap(&env, swap_id, &swap);
+
+        // Refund escrow
+        if swap.payment_amount > 0 {
+            transfer_payment(
+                &env,
+                &get_xlm_token_address(&env),
+                &env.current_contract_address(),
+                &maker,
+                swap.payment_amount,
+            )?;
+        }
+
+        events::emit_swap_cancelled(&env, swap_id, &maker);
+
+        Ok(())
+    }
+
+    // ========== SEALED-BID AUCTIONS ==========
+
+    /// Start a sealed-bid auction for a token the seller owns and has
+    /// approved the marketplace for. Returns the auction ID.
+    pub fn create_sealed_auction(
+        env: Env,
+        seller: Address,
+        nft_contract: Address,
+        token_id: u64,
+        terms: SealedAuctionTerms,
+    ) -> Result<u64, Error> {
+        seller.require_auth();
+
+        if terms.reserve_price <= 0 || terms.deposit <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+
+        let now = env.ledger().timestamp();
+        if terms.bid_end <= now || terms.reveal_end <= terms.bid_end {
+            return Err(Error::InvalidExpiry);
+        }
+
+        check_collection_tradable(&env, &nft_contract)?;
+        check_owner_and_approval(&env, &nft_contract, token_id, &seller)?;
+
+        let auction_id = get_and_increment_auction_counter(&env);
+        let auction = SealedAuction {
+            auction_id,
+            nft_contract: nft_contract.clone(),
+            token_id,
+            seller: seller.clone(),
+            terms: terms.clone(),
+            status: AuctionStatus::Open,
+            bid_count: 0,
+            highest_bidder: None,
+            highest_bid: 0,
+            second_bid: 0,
+            created_at: now,
+            platform_fee_bps: collection_fee_bps(&env, &nft_contract),
+        };
+
+        save_sealed_auction(&env, &auction);
+
+        events::emit_auction_created(&env, auction_id, &nft_contract, token_id, &seller, &terms);
+
+        Ok(auction_id)
+    }
+
+    /// Commit a sealed bid, escrowing the auction's deposit
+    /// `commitment` is `get_bid_commitment(amount, salt, bidder)`.
+    pub fn commit_bid(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+        commitment: BytesN<32>,
+    ) -> Result<(), Error> {
+        bidder.require_auth();
+
+        let mut auction = get_sealed_auction(&env, auction_id)?;
+        auctions::check_bidding_open(&env, &auction)?;
+
+        if bidder == auction.seller {
+            return Err(Error::Unauthorized);
+        }
+
+        if get_sealed_bid(&env, auction_id, &bidder).is_some() {
+            return Err(Error::AlreadyCommitted);
+        }
+
+        transfer_payment(
+            &env,
+            &get_xlm_token_address(&env),
+            &bidder,
+            &env.current_contract_address(),
+            auction.terms.deposit,
+        )?;
+
+        let bid = SealedBid {
+            commitment,
+            escrowed: auction.terms.deposit,
+            revealed_amount: None,
+        };
+        save_sealed_bid(&env, auction_id, &bidder, &bid);
+
+        auction.bid_count += 1;
+        save_sealed_auction(&env, &auction);
+
+        events::emit_bid_committed(&env, auction_id, &bidder);
+
+        Ok(())
+    }
+
+    /// Reveal a committed bid
+    /// Escrows any part of `amount` above the deposit.
+    pub fn reveal_bid(
+        env: Env,
+        auction_id: u64,
+        bidder: Address,
+        amount: i128,
+        salt: BytesN<32>,
+    ) -> Result<(), Error> {
+        bidder.require_auth();
+
+        let mut auction = get_sealed_auction(&env, auction_id)?;
+        auctions::check_reveal_open(&env, &auction)?;
+
+        let mut bid = get_sealed_bid(&env, auction_id, &bidder).ok_or(Error::BidNotFound)?;
+
+        if bid.revealed_amount.is_some()
+            || amount <= 0
+            || auctions::hash_bid(&env, amount, &salt, &bidder) != bid.commitment
+        {
+            return Err(Error::InvalidReveal);
+        }
+
+        if amount > bid.escrowed {
+            transfer_payment(
+                &env,
+                &get_xlm_token_address(&env),
+                &bidder,
+                &env.current_contract_address(),
+                amount - bid.escrowed,
+            )?;
+            bid.escrowed = amount;
+        }
+
+        bid.revealed_amount = Some(amount);
+        save_sealed_bid(&env, auction_id, &bidder, &bid);
+
+        auctions::record_reveal(&mut auction, &bidder, amount);
+        save_sealed_auction(&env, &auction);
+
+        events::emit_bid_revealed(&env, auction_id, &bidder, amount);
+
+        Ok(())
+    }
+
+    /// Settle an auction after the reveal window. Callable by anyone.
+    /// The highest valid bidder receives the token and pays the clearing
+    /// price from escrow, split like any other sale. If nobody met the
+    /// reserve the auction simply ends; if the token can no longer be
+    /// delivered it is cancelled and every bid is refunded.
+    pub fn settle_sealed_auction(env: Env, auction_id: u64) -> Result<AuctionStatus, Error> {
+        let mut auction = get_sealed_auction(&env, auction_id)?;
+
+        if auction.status != AuctionStatus::Open {
+            return Err(Error::AuctionNotOpen);
+        }
+
+        if env.ledger().timestamp() <= auction.terms.reveal_end {
+            return Err(Error::WrongAuctionPhase);
+        }
+
+        let mut price = 0;
+        auction.status = AuctionStatus::Settled;
+
+        if let Some(winner) = auction.highest_bidder.clone() {
+            let clearing_price = auctions::clearing_price(&auction);
+
+            // Check the split and deliver the token before paying anything.
+            // If either fails the auction is cancelled so every bid can be
+            // released, rather than staying open with the escrow locked.
+            let settled = escrowed_sale_split(
+                &env,
+                &auction.nft_contract,
+                auction.token_id,
+                &auction.seller,
+                clearing_price,
+                auction.platform_fee_bps,
+            )
+            .and_then(|split| {
+                transfer_nft(&env, &auction.nft_contract, &auction.seller, &winner, auction.token_id)?;
+                Ok(split)
+            });
+
+            match settled {
+                Ok(split) => {
+                    price = clearing_price;
+                    pay_escrowed_sale(&env, &get_xlm_token_address(&env), &auction.seller, &split)?;
+
+                    // The rest of the winner's escrow is released like any other bid
+                    let mut bid = get_sealed_bid(&env, auction_id, &winner).ok_or(Error::BidNotFound)?;
+                    bid.escrowed -= price;
+                    save_sealed_bid(&env, auction_id, &winner, &bid);
+                }
+                Err(_) => auction.status = AuctionStatus::Cancelled,
+            }
+        }
+
+        save_sealed_auction(&env, &auction);
+
+        events::emit_auction_settled(&env, auction_id, &auction.status, &auction.highest_bidder, price);
+
+        Ok(auction.status)
+    }
+
+    /// Cancel an auction that has no bids yet
+    pub fn cancel_sealed_auction(env: Env, auction_id: u64, seller: Address) -> Result<(), Error> {
+        seller.require_auth();
+
+        let mut auction = get_sealed_auction(&env, auction_id)?;
+
+        if auction.seller != seller {
+            return Err(Error::Unauthorized);
+        }
+
+        if auction.status != AuctionStatus::Open {
+            return Err(Error::AuctionNotOpen);
+        }
+
+        if auction.bid_count > 0 {
+            return Err(Error::AuctionHasBids);
+        }
+
+        auction.status = AuctionStatus::Cancelled;
+        save_sealed_auction(&env, &auction);
+
+        events::emit_auction_cancelled(&env, auction_id, &seller);
+
+        Ok(())
+    }
+
+    /// Release a bid's escrow once the auction is settled or cancelled.
+    /// Callable by anyone. Unrevealed deposits go to the seller's payout
+    /// balance under `UnrevealedPolicy::Forfeit` (settled auctions only);
+    /// everything else is returned to the bidder. Returns the refund.
+    pub fn release_bid(env: Env, auction_id: u64, bidder: Address) -> Result<i128, Error> {
+        let auction = get_sealed_auction(&env, auction_id)?;
+
+        if auction.status == AuctionStatus::Open {
+            return Err(Error::WrongAuctionPhase);
+        }
+
+        let bid = get_sealed_bid(&env, auction_id, &bidder).ok_or(Error::BidNotFound)?;
+        remove_sealed_bid(&env, auction_id, &bidder);
+
+        let payment_token = get_xlm_token_address(&env);
+        let forfeited = bid.revealed_amount.is_none()
+            && auction.status == AuctionStatus::Settled
+            && auction.terms.unrevealed == UnrevealedPolicy::Forfeit;
+
+        let refund = if forfeited {
+            credit_claimable(&env, &auction.seller, &payment_token, bid.escrowed);
+            0
+        } else {
+            if bid.escrowed > 0 {
+                transfer_payment(
+                    &env,
+                    &payment_token,
+                    &env.current_contract_address(),
+                    &bidder,
+                    bid.escrowed,
+                )?;
+            }
+            bid.escrowed
+        };
+
+        events::emit_bid_released(&env, auction_id, &bidder, refund, forfeited);
+
+        Ok(refund)
+    }
+
+    // ========== RAFFLES ==========
+
+    /// Start a raffle, escrowing the token with the marketplace
+    /// Returns the raffle ID.
+    pub fn create_raffle(
+        env: Env,
+        seller: Address,
+        nft_contract: Address,
+        token_id: u64,
+        terms: RaffleTerms,
+    ) -> Result<u64, Error> {
+        seller.require_auth();
+
+        raffles::validate_terms(&env, &terms)?;
+        check_owner_and_approval(&env, &nft_contract, token_id, &seller)?;
+
+        // Checks the collection is tradable
+        transfer_nft(&env, &nft_contract, &seller, &env.current_contract_address(), token_id)?;
+
+        start_raffle(&env, &seller, &nft_contract, token_id, &terms)
+    }
+
+    /// Buy `count` tickets, paying `count * ticket_price` into escrow
+    pub fn buy_tickets(
+        env: Env,
+        raffle_id: u64,
+        buyer: Address,
+        count: u32,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+
+        let mut raffle = get_raffle(&env, raffle_id)?;
+        raffles::check_ticket_sales_open(&env, &raffle)?;
+
+        if buyer == raffle.seller {
+            return Err(Error::Unauthorized);
+        }
+
+        if count == 0 {
+            return Err(Error::InvalidRaffle);
+        }
+
+        let tickets_sold = raffle.tickets_sold.checked_add(count).ok_or(Error::RaffleSoldOut)?;
+        if tickets_sold > raffle.terms.max_tickets {
+            return Err(Error::RaffleSoldOut);
+        }
+
+        transfer_payment(
+            &env,
+            &get_xlm_token_address(&env),
+            &buyer,
+            &env.current_contract_address(),
+            raffle.terms.ticket_price * count as i128,
+        )?;
+
+        raffle.tickets_sold = tickets_sold;
+        add_raffle_entry(&env, raffle_id, &buyer, count, raffle.tickets_sold);
+        save_raffle(&env, &raffle);
+
+        events::emit_tickets_bought(&env, raffle_id, &buyer, count);
+
+        Ok(())
+    }
+
+    /// Close a raffle once it has ended or sold out. Callable by anyone.
+    /// If the minimum was met, a ticket-weighted winner receives the token
+    /// and the proceeds are split like any other sale. Otherwise, or if the
+    /// split can't be settled, the token goes back to the seller and ticket
+    /// holders can claim refunds.
+    pub fn draw_raffle(env: Env, raffle_id: u64) -> Result<Option<Address>, Error> {
+        let mut raffle = get_raffle(&env, raffle_id)?;
+        raffles::check_drawable(&env, &raffle)?;
+
+        let met_minimum = raffle.tickets_sold > 0 && raffle.tickets_sold >= raffle.terms.min_tickets;
+        let winner = if met_minimum && check_collection_tradable(&env, &raffle.nft_contract).is_ok() {
+            raffles::draw_winner(&env, &get_raffle_entries(&env, raffle_id), raffle.tickets_sold)
+        } else {
+            None
+        };
+
+        // A sale whose split can't be settled is cancelled like an unmet
+        // minimum, so ticket holders can still be refunded
+        let sale = winner.and_then(|winner| {
+            escrowed_sale_split(
+                &env,
+                &raffle.nft_contract,
+                raffle.token_id,
+                &raffle.seller,
+                raffle.terms.ticket_price * raffle.tickets_sold as i128,
+                raffle.platform_fee_bps,
+            )
+            .ok()
+            .map(|split| (winner, split))
+        });
+
+        let winner = match sale {
+            Some((winner, split)) => {
+                pay_escrowed_sale(&env, &get_xlm_token_address(&env), &raffle.seller, &split)?;
+                release_escrowed_nft(&env, &raffle.nft_contract, &winner, raffle.token_id);
+                raffle.status = RaffleStatus::Drawn;
+                Some(winner)
+            }
+            None => {
+                release_escrowed_nft(&env, &raffle.nft_contract, &raffle.seller, raffle.token_id);
+                raffle.status = RaffleStatus::Cancelled;
+                None
+            }
+        };
+
+        raffle.winner = winner.clone();
+        save_raffle(&env, &raffle);
+
+        events::emit_raffle_drawn(&env, raffle_id, &raffle.status, &winner, raffle.tickets_sold);
+
+        Ok(winner)
+    }
+
+    /// Refund a ticket holder of a cancelled raffle. Callable by anyone.
+    /// Returns the amount refunded.
+    pub fn refund_tickets(env: Env, raffle_id: u64, buyer: Address) -> Result<i128, Error> {
+        let raffle = get_raffle(&env, raffle_id)?;
+
+        let tickets = get_raffle_tickets(&env, raffle_id, &buyer);
+        if raffle.status != RaffleStatus::Cancelled || tickets == 0 {
+            return Err(Error::NothingToWithdraw);
+        }
+
+        remove_raffle_tickets(&env, raffle_id, &buyer);
+
+        let refund = raffle.terms.ticket_price * tickets as i128;
+        transfer_payment(
+            &env,
+            &get_xlm_token_address(&env),
+            &env.current_contract_address(),
+            &buyer,
+            refund,
+        )?;
+
+        events::emit_tickets_refunded(&env, raffle_id, &buyer, refund);
+
+        Ok(refund)
+    }
+
+    // ========== SIGNED ORDERS ==========
+
+    /// Register the ed25519 public key used to sign off-chain orders
+    pub fn set_order_signer(
+        env: Env,
+        seller: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), Error> {
+        seller.require_auth();
+
+        set_order_signer(&env, &seller, &public_key);
+
+        events::emit_order_signer_set(&env, &seller, &public_key);
+
+        Ok(())
+    }
+
+    /// Fill an off-chain signed order
+    /// Settles through the same fee/royalty path as `buy`
+    pub fn fulfill_order(
+        env: Env,
+        order: Order,
+        signature: BytesN<64>,
+        buyer: Address,
+    ) -> Result<(), Error> {
+        buyer.require_auth();
+
+        // Validate price
+        if order.price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+
+        // Check expiry, nonce and fill/cancel registry
+        let order_hash = orders::hash_order(&env, &order);
+        orders::check_order_fillable(&env, &order, &order_hash)?;
+
+        // Cannot buy your own order
+        if order.seller == buyer {
+            return Err(Error::CannotBuyOwnListing);
+        }
+
+        // Verify seller signature
+        let public_key = get_order_signer(&env, &order.seller)
+            .ok_or(Error::OrderSignerNotSet)?;
+        orders::verify_order_signature(&env, &public_key, &order_hash, &signature);
+
+        // Mark filled before external calls
+        set_order_state(&env, &order_hash, &OrderState::Filled);
+
+        let items = Vec::from_array(&env, [(order.nft_contract.clone(), order.token_id)]);
+        let split = settle_sale(
+            &env,
+            &Sale {
+                payment_token: order.payment_token.clone(),
+                payer: buyer.clone(),
+                platform_fee_bps: current_fees(&env, &items),
+                items,
+                seller: order.seller.clone(),
+                price: order.price,
+            },
+            &buyer,
+            &None,
+        )?;
+
+        events::emit_order_filled(
+            &env,
+            &order_hash,
+            &order,
+            &buyer,
+            split.platform_fee,
+            split.royalty_amount,
+        );
+
+        Ok(())
+    }
+
+    /// Cancel specific signed orders
+    /// Orders that are already filled or cancelled are skipped
+    pub fn cancel_orders(
+        env: Env,
+        seller: Address,
+        orders: Vec<Order>,
+    ) -> Result<(), Error> {
+        seller.require_auth();
+
+        for order in orders.iter() {
+            if order.seller != seller {
+                return Err(Error::Unauthorized);
+            }
+
+            let order_hash = orders::hash_order(&env, &order);
+            if get_order_state(&env, &order_hash).is_none() {
+                set_order_state(&env, &order_hash, &OrderState::Cancelled);
+                events::emit_order_cancelled(&env, &order_hash, &seller);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cancel all outstanding signed orders by bumping the seller nonce
+    /// Returns the new nonce
+    pub fn increment_seller_nonce(env: Env, seller: Address) -> u64 {
+        seller.require_auth();
+
+        let nonce = increment_seller_nonce(&env, &seller);
+
+        events::emit_nonce_incremented(&env, &seller, nonce);
+
+        nonce
+    }
+
+    // ========== PAYOUTS ==========
+
+    /// Withdraw everything the marketplace holds for `recipient` in `token`
+    /// Returns the amount transferred.
+    pub fn withdraw(env: Env, recipient: Address, token: Address) -> Result<i128, Error> {
+        recipient.require_auth();
+
+        let amount = get_claimable(&env, &recipient, &token);
+        if amount == 0 {
+            return Err(Error::NothingToWithdraw);
+        }
+
+        set_claimable(&env, &recipient, &token, 0);
+        transfer_payment(&env, &token, &env.current_contract_address(), &recipient, amount)?;
+
+        events::emit_withdrawn(&env, &recipient, &token, amount);
+
+        Ok(amount)
+    }
+
+    // ========== VIEW FUNCTIONS ==========
+
+    pub fn get_listing(env: Env, listing_id: u64) -> Result<Listing, Error> {
+        get_listing(&env, listing_id)
+    }
+
+    pub fn get_offer(env: Env, offer_id: u64) -> Result<Offer, Error> {
+        get_offer(&env, offer_id)
+    }
+
+    /// Amount held for an offer raised by an accepted counter-offer
+    pub fn get_offer_escrow(env: Env, offer_id: u64) -> i128 {
+        get_offer_escrow(&env, offer_id)
+    }
+
+    /// Get a page of active listing IDs (max 100 per page)
+    /// Returns (listing_ids, total_count)
+    pub fn active_listings(env: Env, offset: u32, limit: u32) -> (Vec<u64>, u32) {
+        index_page(&env, &IdIndex::ActiveListings, offset, limit.min(100))
+    }
+
+    /// Get a page of active listing IDs for one NFT collection
+    /// Bundles appear under every collection they contain
+    pub fn active_listings_by_collection(
+        env: Env,
+        nft_contract: Address,
+        offset: u32,
+        limit: u32,
+    ) -> (Vec<u64>, u32) {
+        index_page(&env, &IdIndex::CollectionListings(nft_contract), offset, limit.min(100))
+    }
+
+    /// Get a page of active listing IDs created by one seller
+    pub fn active_listings_by_seller(
+        env: Env,
+        seller: Address,
+        offset: u32,
+        limit: u32,
+    ) -> (Vec<u64>, u32) {
+        index_page(&env, &IdIndex::SellerListings(seller), offset, limit.min(100))
+    }
+
+    /// Active listing (single or bundle) covering a token, if any
+    pub fn listing_for_token(env: Env, nft_contract: Address, token_id: u64) -> Option<u64> {
+        get_token_listing(&env, &nft_contract, token_id)
+    }
+
+    /// Get a page of open offer IDs for a token (max 100 per page)
+    /// Returns (offer_ids, total_count)
+    pub fn offers_for_token(
+        env: Env,
+        nft_contract: Address,
+        token_id: u64,
+        offset: u32,
+        limit: u32,
+    ) -> (Vec<u64>, u32) {
+        index_page(&env, &IdIndex::TokenOffers(nft_contract, token_id), offset, limit.min(100))
+    }
+
+    /// Get a page of open offer IDs made by a buyer
+    pub fn offers_by_buyer(
+        env: Env,
+        buyer: Address,
+        offset: u32,
+        limit: u32,
+    ) -> (Vec<u64>, u32) {
+        index_page(&env, &IdIndex::BuyerOffers(buyer), offset, limit.min(100))
+    }
+
+    /// Expired listings still in the active index, scanning one page of
+    /// `active_listings` (max 100 per page).
+    /// Returns (listing_ids, total_active_count) so callers can keep paging.
+    pub fn expired_listings(env: Env, offset: u32, limit: u32) -> (Vec<u64>, u32) {
+        let (page, total) = index_page(&env, &IdIndex::ActiveListings, offset, limit.min(100));
+
+        let mut expired = Vec::new(&env);
+        for listing_id in page.iter() {
+            if let Ok(listing) = get_listing(&env, listing_id) {
+                if is_listing_expired(&env, &listing) {
+                    expired.push_back(listing_id);
+                }
+            }
+        }
+
+        (expired, total)
+    }
+
+    /// Expired offers still stored, scanning one page of open offers
+    /// (max 100 per page).
+    /// Returns (offer_ids, total_open_count) so callers can keep paging.
+    pub fn expired_offers(env: Env, offset: u32, limit: u32) -> (Vec<u64>, u32) {
+        let (page, total) = index_page(&env, &IdIndex::OpenOffers, offset, limit.min(100));
+        let now = env.ledger().timestamp();
+
+        let mut expired = Vec::new(&env);
+        for offer_id in page.iter() {
+            if let Ok(offer) = get_offer(&env, offer_id) {
+                if offer.expires_at.is_some_and(|expiry| now > expiry) {
+                    expired.push_back(offer_id);
+                }
+            }
+        }
+
+        (expired, total)
+    }
+
+    /// Get every price a listing has had, oldest first
+    pub fn get_price_history(env: Env, listing_id: u64) -> Vec<PricePoint> {
+        get_price_history(&env, listing_id)
+    }
+
+    pub fn get_counter_offer(env: Env, counter_id: u64) -> Result<CounterOffer, Error> {
+        get_counter_offer(&env, counter_id)
+    }
+
+    /// Open counter-offer IDs in the thread started by offer `thread_id`
+    pub fn get_offer_thread(env: Env, thread_id: u64) -> Vec<u64> {
+        get_offer_thread(&env, thread_id)
+    }
+
+    pub fn get_sealed_auction(env: Env, auction_id: u64) -> Result<SealedAuction, Error> {
+        get_sealed_auction(&env, auction_id)
+    }
+
+    pub fn get_sealed_bid(env: Env, auction_id: u64, bidder: Address) -> Option<SealedBid> {
+        get_sealed_bid(&env, auction_id, &bidder)
+    }
+
+    /// Commitment to pass to `commit_bid` (compute off chain in production)
+    pub fn get_bid_commitment(env: Env, amount: i128, salt: BytesN<32>, bidder: Address) -> BytesN<32> {
+        auctions::hash_bid(&env, amount, &salt, &bidder)
+    }
+
+    pub fn get_raffle(env: Env, raffle_id: u64) -> Result<Raffle, Error> {
+        get_raffle(&env, raffle_id)
+    }
+
+    /// Tickets `buyer` holds in a raffle (0 once refunded)
+    pub fn get_raffle_tickets(env: Env, raffle_id: u64, buyer: Address) -> u32 {
+        get_raffle_tickets(&env, raffle_id, &buyer)
+    }
+
+    pub fn get_swap(env: Env, swap_id: u64) -> Result<Swap, Error> {
+        get_swap(&env, swap_id)
+    }
+
+    /// Get a page of active listing IDs reserved for a buyer
+    /// Returns (listing_ids, total_count)
+    pub fn get_reserved_listings(
+        env: Env,
+        buyer: Address,
+        offset: u32,
+        limit: u32,
+    ) -> (Vec<u64>, u32) {
+        index_page(&env, &IdIndex::ReservedListings(buyer), offset, limit.min(100))
+    }
+
+    /// Hash a seller must sign for an off-chain order
+    pub fn get_order_hash(env: Env, order: Order) -> BytesN<32> {
+        orders::hash_order(&env, &order)
+    }
+
+    /// Filled/cancelled state of an order (None if still open)
+    pub fn get_order_state(env: Env, order_hash: BytesN<32>) -> Option<OrderState> {
+        get_order_state(&env, &order_hash)
+    }
+
+    pub fn get_order_signer(env: Env, seller: Address) -> Option<BytesN<32>> {
+        get_order_signer(&env, &seller)
+    }
+
+    pub fn get_seller_nonce(env: Env, seller: Address) -> u64 {
+        get_seller_nonce(&env, &seller)
+    }
+
+    pub fn get_platform_fee(env: Env) -> u32 {
+        get_platform_fee(&env)
+    }
+
+    /// Fee change that has been scheduled but not yet taken effect
+    pub fn get_scheduled_fee_change(env: Env) -> Option<ScheduledFeeChange> {
+        get_scheduled_fee_change(&env).filter(|change| !fee_schedule::is_due(&env, change))
+    }
+
+    pub fn get_max_royalty(env: Env) -> u32 {
+        get_max_royalty_bps(&env)
+    }
+
+    /// Registry status of a collection (None if unregistered)
+    pub fn get_collection_status(env: Env, nft_contract: Address) -> Option<CollectionStatus> {
+        get_collection_status(&env, &nft_contract)
+    }
+
+    /// Platform fee override for a collection (None uses the global fee)
+    pub fn get_collection_fee(env: Env, nft_contract: Address) -> Option<u32> {
+        get_collection_fee(&env, &nft_contract)
+    }
+
+    /// Collection fee change that has been scheduled but not yet taken effect
+    pub fn get_collection_fee_change(env: Env, nft_contract: Address) -> Option<ScheduledFeeChange> {
+        get_scheduled_collection_fee_change(&env, &nft_contract)
+            .filter(|change| !fee_schedule::is_due(&env, change))
+    }
+
+    pub fn is_verified_collection(env: Env, nft_contract: Address) -> bool {
+        get_collection_status(&env, &nft_contract) == Some(CollectionStatus::Verified)
+    }
+
+    pub fn get_trading_mode(env: Env) -> TradingMode {
+        get_trading_mode(&env)
+    }
+
+    pub fn get_fee_tiers(env: Env) -> Vec<FeeTier> {
+        get_fee_tiers(&env)
+    }
+
+    /// Membership NFT contract and its holders' fee discount (bps of the fee)
+    pub fn get_membership_discount(env: Env) -> Option<(Address, u32)> {
+        get_membership_discount(&env)
+    }
+
+    pub fn get_referral_share(env: Env) -> u32 {
+        get_referral_share(&env)
+    }
+
+    /// Share of the platform fee (bps) a referrer receives, or None if
+    /// the referrer isn't registered
+    pub fn get_referrer_share(env: Env, referrer: Address) -> Option<u32> {
+        if is_referrer_registered(&env, &referrer) {
+            Some(get_referrer_share(&env, &referrer))
+        } else {
+            None
+        }
+    }
+
+    /// Completed sales counted towards a seller's fee tier
+    pub fn get_seller_sales(env: Env, seller: Address) -> u32 {
+        get_seller_sales(&env, &seller)
+    }
+
+    /// Preview what `buy` would pay out for a listing, without a referrer
+    /// Uses the same fee and royalty computation as settlement.
+    pub fn preview_buy(env: Env, listing_id: u64) -> Result<SalePreview, Error> {
+        let listing = get_listing(&env, listing_id)?;
+        check_listing_available(&env, &listing)?;
+
+        preview_sale(
+            &env,
+            get_xlm_token_address(&env),
+            &listed_tokens(&env, &listing),
+            &listing.seller,
+            listing.price,
+            &listing.platform_fee_bps,
+        )
+    }
+
+    /// Preview what `accept_offer` would pay out if the token's current
+    /// owner accepted the offer now, without a referrer
+    pub fn preview_accept_offer(env: Env, offer_id: u64) -> Result<SalePreview, Error> {
+        use nft_interface::NFTContractClient;
+
+        let offer = get_offer(&env, offer_id)?;
+
+        if let Some(expiry) = offer.expires_at {
+            if env.ledger().timestamp() > expiry {
+                return Err(Error::OfferExpired);
+            }
+        }
+
+        let seller = match NFTContractClient::new(&env, &offer.nft_contract).try_owner_of(&offer.token_id) {
+            Ok(Ok(owner)) => owner,
+            _ => return Err(Error::NotTokenOwner),
+        };
+
+        let items = Vec::from_array(&env, [(offer.nft_contract.clone(), offer.token_id)]);
+        preview_sale(
+            &env,
+            get_xlm_token_address(&env),
+            &items,
+            &seller,
+            offer.amount,
+            &current_fees(&env, &items),
+        )
+    }
+
+    /// Fee, royalty and proceeds breakdown if `seller` sold the token at `price`
+    /// Applies the collection fee override, the seller's volume tier and
+    /// membership discount, exactly as a sale would.
+    pub fn quote_fees(
+        env: Env,
+        nft_contract: Address,
+        token_id: u64,
+        price: i128,
+        seller: Address,
+    ) -> Result<FeeQuote, Error> {
+        if price <= 0 {
+            return Err(Error::InvalidPrice);
+        }
+
+        let items = Vec::from_array(&env, [(nft_contract, token_id)]);
+        compute_sale_split(
+            &env,
+            &items,
+            price,
+            &seller,
+            &current_fees(&env, &items),
+            &None,
+        )
+    }
+
+    /// Fees and royalties held for `recipient` in `token`, awaiting `withdraw`
+    pub fn claimable(env: Env, recipient: Address, token: Address) -> i128 {
+        get_claimable(&env, &recipient, &token)
+    }
+
+    pub fn get_payout_mode(env: Env, recipient: Address) -> PayoutMode {
+        get_payout_mode(&env, &recipient)
+    }
+
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        get_admin(&env)
+    }
+
+    // ========== ADMIN FUNCTIONS ==========
+    // Each admin function applies an `AdminAction`. While governance is
+    // enabled they fail with `Unauthorized`; propose the action instead.
+
+    /// Lower the platform fee immediately
+    /// Increases fail with `InvalidFee`; use `schedule_fee_change`. Also
+    /// cancels any scheduled change.
+    pub fn set_platform_fee(
+        env: Env,
+        admin: Address,
+        new_fee_bps: u32,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetPlatformFee(new_fee_bps))
+    }
+
+    /// Schedule a platform fee change, replacing any pending one
+    /// `effective_at` must give at least a week's notice and be 30 days
+    /// after the previous scheduled change took effect; the fee may move by
+    /// at most 100 bps. Existing listings keep the fee they were created with.
+    pub fn schedule_fee_change(
+        env: Env,
+        admin: Address,
+        new_fee_bps: u32,
+        effective_at: u64,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::ScheduleFeeChange(new_fee_bps, effective_at))
+    }
+
+    pub fn cancel_fee_change(env: Env, admin: Address) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::CancelFeeChange)
+    }
+
+    /// Set the highest royalty (bps of the sale price) the marketplace pays
+    /// Sales of tokens whose collection reports more fail with `InvalidRoyalty`.
+    pub fn set_max_royalty(
+        env: Env,
+        admin: Address,
+        max_royalty_bps: u32,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetMaxRoyalty(max_royalty_bps))
+    }
+
+    pub fn set_fee_recipient(
+        env: Env,
+        admin: Address,
+        new_recipient: Address,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetFeeRecipient(new_recipient))
+    }
+
+    /// Set a collection's registry status (None removes it from the registry)
+    pub fn set_collection_status(
+        env: Env,
+        admin: Address,
+        nft_contract: Address,
+        status: Option<CollectionStatus>,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+
+        let action = match status {
+            Some(status) => AdminAction::SetCollectionStatus(nft_contract, status),
+            None => AdminAction::ClearCollectionStatus(nft_contract),
+        };
+        apply_admin_action(&env, action)
+    }
+
+    /// Lower the platform fee for one collection (None restores the global
+    /// fee). Fails with `InvalidFee` if this would raise the collection's
+    /// fee; increases must be scheduled with `schedule_collection_fee_change`.
+    pub fn set_collection_fee(
+        env: Env,
+        admin: Address,
+        nft_contract: Address,
+        fee_bps: Option<u32>,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetCollectionFee(nft_contract, fee_bps))
+    }
+
+    /// Schedule a collection fee change, replacing any pending one
+    /// Same notice, spacing and step limits as `schedule_fee_change`,
+    /// measured from the collection's current fee. Existing listings keep
+    /// the fee they were created with.
+    pub fn schedule_collection_fee_change(
+        env: Env,
+        admin: Address,
+        nft_contract: Address,
+        new_fee_bps: u32,
+        effective_at: u64,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(
+            &env,
+            AdminAction::ScheduleCollectionFeeChange(nft_contract, new_fee_bps, effective_at),
+        )
+    }
+
+    pub fn cancel_collection_fee_change(
+        env: Env,
+        admin: Address,
+        nft_contract: Address,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::CancelCollectionFeeChange(nft_contract))
+    }
+
+    /// Switch between trading any non-blocked collection and only
+    /// registered (verified or allowed) ones
+    pub fn set_trading_mode(
+        env: Env,
+        admin: Address,
+        mode: TradingMode,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetTradingMode(mode))
+    }
+
+    /// Set seller volume tiers (max 10, ascending `min_sales`, each fee at most 10%)
+    /// A seller pays at most the fee of the highest tier they have reached.
+    pub fn set_fee_tiers(
+        env: Env,
+        admin: Address,
+        tiers: Vec<FeeTier>,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetFeeTiers(tiers))
+    }
+
+    /// Give holders of a membership NFT a fee discount
+    /// discount_bps: share of the platform fee waived (10000 = no fee).
+    /// Pass None to remove the discount.
+    pub fn set_membership_discount(
+        env: Env,
+        admin: Address,
+        membership: Option<(Address, u32)>,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+
+        let action = match membership {
+            Some((membership_nft, discount_bps)) => {
+                AdminAction::SetMembershipDiscount(membership_nft, discount_bps)
+            }
+            None => AdminAction::RemoveMembershipDiscount,
+        };
+        apply_admin_action(&env, action)
+    }
+
+    /// Set the default share of the platform fee paid to referrers
+    /// share_bps: bps of the platform fee (10000 = the whole fee)
+    pub fn set_referral_share(
+        env: Env,
+        admin: Address,
+        share_bps: u32,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetReferralShare(share_bps))
+    }
+
+    /// Register (or update) a referrer, optionally with its own share
+    /// Pass None for `share_bps` to use the default referral share.
+    pub fn register_referrer(
+        env: Env,
+        admin: Address,
+        referrer: Address,
+        share_bps: Option<u32>,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::RegisterReferrer(referrer, share_bps))
+    }
+
+    pub fn remove_referrer(
+        env: Env,
+        admin: Address,
+        referrer: Address,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::RemoveReferrer(referrer))
+    }
+
+    /// Choose whether a fee or royalty recipient is paid during the sale
+    /// (`Push`) or accrues a balance to `withdraw` (`Pull`)
+    pub fn set_payout_mode(
+        env: Env,
+        admin: Address,
+        recipient: Address,
+        mode: PayoutMode,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetPayoutMode(recipient, mode))
+    }
+
+    // ========== GOVERNANCE ==========
+
+    /// Hand admin operations over to M-of-N signers with a timelock
+    /// Only the admin can enable it; afterwards it can only be changed or
+    /// disabled by a `SetGovernance` or `DisableGovernance` proposal.
+    pub fn enable_governance(
+        env: Env,
+        admin: Address,
+        config: GovernanceConfig,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetGovernance(config))
+    }
+
+    /// Propose an admin action (signers only). Counts as the proposer's
+    /// approval. Returns the proposal ID.
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        action: AdminAction,
+    ) -> Result<u64, Error> {
+        proposer.require_auth();
+
+        let config = get_governance(&env).ok_or(Error::Unauthorized)?;
+        governance::check_signer(&config, &proposer)?;
+
+        let proposal_id = get_and_increment_proposal_counter(&env);
+        let mut proposal = Proposal {
+            proposal_id,
+            action: action.clone(),
+            proposer: proposer.clone(),
+            approvals: Vec::new(&env),
+            status: ProposalStatus::Pending,
+            created_at: env.ledger().timestamp(),
+            executable_at: None,
+        };
+        governance::record_approval(&env, &config, &mut proposal.approvals, &mut proposal.executable_at, &proposer)?;
+
+        save_proposal(&env, &proposal);
+
+        events::emit_proposal_created(&env, proposal_id, &proposer, &action);
+
+        Ok(proposal_id)
+    }
+
+    /// Approve a pending proposal (signers only)
+    pub fn approve_proposal(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<(), Error> {
+        signer.require_auth();
+
+        let config = get_governance(&env).ok_or(Error::Unauthorized)?;
+        governance::check_signer(&config, &signer)?;
+
+        let mut proposal = get_proposal(&env, proposal_id)?;
+        governance::check_pending(&proposal.status)?;
+        governance::record_approval(&env, &config, &mut proposal.approvals, &mut proposal.executable_at, &signer)?;
+
+        save_proposal(&env, &proposal);
+
+        events::emit_proposal_approved(&env, proposal_id, &signer, proposal.executable_at);
+
+        Ok(())
+    }
+
+    /// Execute an approved proposal once its timelock has passed.
+    /// Callable by anyone.
+    pub fn execute_proposal(env: Env, proposal_id: u64) -> Result<(), Error> {
+        let config = get_governance(&env).ok_or(Error::Unauthorized)?;
+
+        let mut proposal = get_proposal(&env, proposal_id)?;
+        governance::check_executable(&env, &config, &proposal.status, &proposal.approvals, proposal.executable_at)?;
+
+        proposal.status = ProposalStatus::Executed;
+        save_proposal(&env, &proposal);
+
+        apply_admin_action(&env, proposal.action)?;
+
+        events::emit_proposal_executed(&env, proposal_id);
+
+        Ok(())
+    }
+
+    /// Cancel a pending proposal (any signer)
+    pub fn cancel_proposal(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<(), Error> {
+        signer.require_auth();
+
+        let config = get_governance(&env).ok_or(Error::Unauthorized)?;
+        governance::check_signer(&config, &signer)?;
+
+        let mut proposal = get_proposal(&env, proposal_id)?;
+        governance::check_pending(&proposal.status)?;
+
+        proposal.status = ProposalStatus::Cancelled;
+        save_proposal(&env, &proposal);
+
+        events::emit_proposal_cancelled(&env, proposal_id, &signer);
+
+        Ok(())
+    }
+
+    pub fn get_governance(env: Env) -> Option<GovernanceConfig> {
+        get_governance(&env)
+    }
+
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error> {
+        get_proposal(&env, proposal_id)
+    }
+}
+
+/// Verify `admin` is the admin and may act directly (governance disabled)
+fn check_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+    admin.require_auth();
+
+    let current_admin = get_admin(env)?;
+    if current_admin != *admin {
+        return Err(Error::Unauthorized);
+    }
+
+    if get_governance(env).is_some() {
+        return Err(Error::Unauthorized);
+    }
+
+    Ok(())
+}
+
+/// Write back a scheduled fee change that has taken effect
+/// Until then `get_platform_fee` already reports it.
+fn apply_due_fee_change(env: &Env) {
+    if let Some(change) = get_scheduled_fee_change(env) {
+        if fee_schedule::is_due(env, &change) {
+            set_platform_fee(env, change.fee_bps);
+            set_last_fee_change(env, change.effective_at);
+            set_scheduled_fee_change(env, &None);
+        }
+    }
+}
+
+/// Write back a collection's scheduled fee change once it is due
+fn apply_due_collection_fee_change(env: &Env, nft_contract: &Address) {
+    if let Some(change) = get_scheduled_collection_fee_change(env, nft_contract) {
+        if fee_schedule::is_due(env, &change) {
+            set_collection_fee(env, nft_contract, Some(change.fee_bps));
+            set_last_collection_fee_change(env, nft_contract, change.effective_at);
+            set_scheduled_collection_fee_change(env, nft_contract, &None);
+        }
+    }
+}
+
+/// Validate and apply an admin action
+/// Shared by the direct admin functions and executed proposals.
+fn apply_admin_action(env: &Env, action: AdminAction) -> Result<(), Error> {
+    match action {
+        AdminAction::SetPlatformFee(new_fee_bps) => {
+            apply_due_fee_change(env);
+
+            // Increases need notice: see `ScheduleFeeChange`
+            if new_fee_bps > get_platform_fee(env) {
+                return Err(Error::InvalidFee);
+            }
+
+            set_platform_fee(env, new_fee_bps);
+
+            events::emit_fee_updated(env, new_fee_bps);
+
+            // A pending change was checked against the old fee
+            if get_scheduled_fee_change(env).is_some() {
+                set_scheduled_fee_change(env, &None);
+
+                events::emit_fee_change_scheduled(env, &None);
+            }
+        }
+        AdminAction::ScheduleFeeChange(fee_bps, effective_at) => {
+            apply_due_fee_change(env);
+
+            let change = ScheduledFeeChange { fee_bps, effective_at };
+            fee_schedule::validate_change(env, get_platform_fee(env), get_last_fee_change(env), &change)?;
+
+            let change = Some(change);
+            set_scheduled_fee_change(env, &change);
+
+            events::emit_fee_change_scheduled(env, &change);
+        }
+        AdminAction::CancelFeeChange => {
+            apply_due_fee_change(env);
+
+            if get_scheduled_fee_change(env).is_some() {
+                set_scheduled_fee_change(env, &None);
+
+                events::emit_fee_change_scheduled(env, &None);
+            }
+        }
+        AdminAction::SetMaxRoyalty(max_royalty_bps) => {
+            if !validate_royalty_bps(max_royalty_bps) {
+                return Err(Error::InvalidRoyalty);
+            }
+
+            set_max_royalty_bps(env, max_royalty_bps);
+
+            events::emit_max_royalty_updated(env, max_royalty_bps);
+        }
+        AdminAction::SetFeeRecipient(new_recipient) => {
+            set_fee_recipient(env, &new_recipient);
+
+            events::emit_fee_recipient_updated(env, &new_recipient);
+        }
+        AdminAction::SetCollectionStatus(nft_contract, status) => {
+            let status = Some(status);
+            set_collection_status(env, &nft_contract, &status);
+
+            events::emit_collection_status_set(env, &nft_contract, &status);
+        }
+        AdminAction::ClearCollectionStatus(nft_contract) => {
+            let status = None;
+            set_collection_status(env, &nft_contract, &status);
+
+            events::emit_collection_status_set(env, &nft_contract, &status);
+        }
+        AdminAction::SetCollectionFee(nft_contract, fee_bps) => {
+            apply_due_collection_fee_change(env, &nft_contract);
+
+            // Increases need notice: see `ScheduleCollectionFeeChange`.
+            // Clearing the override counts as moving to the global fee.
+            let new_fee_bps = fee_bps.unwrap_or_else(|| get_platform_fee(env));
+            if new_fee_bps > collection_fee_bps(env, &nft_contract) {
+                return Err(Error::InvalidFee);
+            }
+
+            set_collection_fee(env, &nft_contract, fee_bps);
+
+            events::emit_collection_fee_set(env, &nft_contract, fee_bps);
+
+            // A pending change was checked against the old fee
+            if get_scheduled_collection_fee_change(env, &nft_contract).is_some() {
+                set_scheduled_collection_fee_change(env, &nft_contract, &None);
+
+                events::emit_collection_fee_change_scheduled(env, &nft_contract, &None);
+            }
+        }
+        AdminAction::ScheduleCollectionFeeChange(nft_contract, fee_bps, effective_at) => {
+            apply_due_collection_fee_change(env, &nft_contract);
+
+            let change = ScheduledFeeChange { fee_bps, effective_at };
+            fee_schedule::validate_change(
+                env,
+                collection_fee_bps(env, &nft_contract),
+                get_last_collection_fee_change(env, &nft_contract),
+                &change,
+            )?;
+
+            let change = Some(change);
+            set_scheduled_collection_fee_change(env, &nft_contract, &change);
+
+            events::emit_collection_fee_change_scheduled(env, &nft_contract, &change);
+        }
+        AdminAction::CancelCollectionFeeChange(nft_contract) => {
+            apply_due_collection_fee_change(env, &nft_contract);
+
+            if get_scheduled_collection_fee_change(env, &nft_contract).is_some() {
+                set_scheduled_collection_fee_change(env, &nft_contract, &None);
+
+                events::emit_collection_fee_change_scheduled(env, &nft_contract, &None);
+            }
+        }
+        AdminAction::SetTradingMode(mode) => {
+            set_trading_mode(env, mode);
+
+            events::emit_trading_mode_set(env, mode);
+        }
+        AdminAction::SetFeeTiers(tiers) => {
+            if tiers.len() > 10 {
+                return Err(Error::InvalidFee);
+            }
+
+            let mut previous_min: Option<u32> = None;
+            for tier in tiers.iter() {
+                if tier.fee_bps > 1000 || previous_min.is_some_and(|min| tier.min_sales <= min) {
+                    return Err(Error::InvalidFee);
+                }
+                previous_min = Some(tier.min_sales);
+            }
+
+            set_fee_tiers(env, &tiers);
+
+            events::emit_fee_tiers_set(env, &tiers);
+        }
+        AdminAction::SetMembershipDiscount(membership_nft, discount_bps) => {
+            if discount_bps > 10000 {
+                return Err(Error::InvalidFee);
+            }
+
+            let membership = Some((membership_nft, discount_bps));
+            set_membership_discount(env, &membership);
+
+            events::emit_membership_discount_set(env, &membership);
+        }
+        AdminAction::RemoveMembershipDiscount => {
+            set_membership_discount(env, &None);
+
+            events::emit_membership_discount_set(env, &None);
+        }
+        AdminAction::SetReferralShare(share_bps) => {
+            if share_bps > 10000 {
+                return Err(Error::InvalidFee);
+            }
+
+            set_referral_share(env, share_bps);
+
+            events::emit_referral_share_set(env, share_bps);
+        }
+        AdminAction::RegisterReferrer(referrer, share_bps) => {
+            if share_bps.is_some_and(|share_bps| share_bps > 10000) {
+                return Err(Error::InvalidFee);
+            }
+
+            register_referrer(env, &referrer, share_bps);
+
+            events::emit_referrer_registered(env, &referrer, share_bps);
+        }
+        AdminAction::RemoveReferrer(referrer) => {
+            remove_referrer(env, &referrer);
+
+            events::emit_referrer_removed(env, &referrer);
+        }
+        AdminAction::SetPayoutMode(recipient, mode) => {
+            set_payout_mode(env, &recipient, mode);
+
+            events::emit_payout_mode_set(env, &recipient, mode);
+        }
+        AdminAction::SetGovernance(config) => {
+            governance::validate_config(&config)?;
+
+            let config = Some(config);
+            set_governance(env, &config);
+
+            events::emit_governance_set(env, &config);
+        }
+        AdminAction::DisableGovernance => {
+            set_governance(env, &None);
+
+            events::emit_governance_set(env, &None);
+        }
+    }
+
+    Ok(())
+}
+
+/// Mark a listing `Invalidated` and drop it from the active indexes
+fn invalidate_listing(env: &Env, mut listing: Listing) {
+    listing.status = ListingStatus::Invalidated;
+    save_listing(env, listing.listing_id, &listing);
+    remove_active_listing(env, &listing);
+
+    events::emit_listing_invalidated(env, listing.listing_id, &listing.seller);
+}
+
+/// Invalidate the active listing for a token that just left `from`
+/// Covers moves made by the marketplace itself, which NFT contracts don't
+/// report through `on_token_moved`.
+fn invalidate_token_listing(env: &Env, nft_contract: &Address, token_id: u64, from: &Address) {
+    let listing = get_token_listing(env, nft_contract, token_id)
+        .and_then(|listing_id| get_listing(env, listing_id).ok());
+
+    if let Some(listing) = listing {
+        if listing.status == ListingStatus::Active && listing.seller == *from {
+            invalidate_listing(env, listing);
+        }
+    }
+}
+
+/// Whether an item list names the same token more than once
+fn has_duplicate_items(items: &Vec<(Address, u64)>) -> bool {
+    items
+        .iter()
+        .enumerate()
+        .any(|(index, item)| items.first_index_of(item) != Some(index as u32))
+}
+
+/// Whether the seller still owns, and the marketplace may move, every
+/// token in a listing
+fn is_listing_deliverable(env: &Env, listing: &Listing) -> bool {
+    listed_tokens(env, listing)
+        .iter()
+        .all(|(nft_contract, token_id)| is_owned_and_approved(env, &nft_contract, token_id, &listing.seller))
+}
+
+/// Check a token can be listed
+///
+/// A token has at most one active listing. An expired or undeliverable one
+/// is cleared to make way; one that can still be filled fails with
+/// `ListingStillValid`.
+fn check_not_listed(env: &Env, nft_contract: &Address, token_id: u64) -> Result<(), Error> {
+    let listing = get_token_listing(env, nft_contract, token_id)
+        .and_then(|listing_id| get_listing(env, listing_id).ok());
+
+    match listing {
+        Some(listing) if listing.status == ListingStatus::Active => {
+            if is_listing_expired(env, &listing) {
+                expire_listing(env, listing);
+            } else if is_listing_deliverable(env, &listing) {
+                return Err(Error::ListingStillValid);
+            } else {
+                invalidate_listing(env, listing);
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether a listing is past its expiry (or was already marked `Expired`)
+fn is_listing_expired(env: &Env, listing: &Listing) -> bool {
+    match listing.status {
+        ListingStatus::Expired => true,
+        ListingStatus::Active => listing
+            .expires_at
+            .is_some_and(|expiry| env.ledger().timestamp() > expiry),
+        _ => false,
+    }
+}
+
+/// Drop an expired listing from the indexes and delete its storage
+fn expire_listing(env: &Env, listing: Listing) {
+    remove_active_listing(env, &listing);
+    remove_listing(env, listing.listing_id);
+    remove_price_history(env, listing.listing_id);
+
+    events::emit_listing_expired(env, listing.listing_id, &listing.seller);
+}
+
+/// Delete an offer, its index entries and every counter to it
+/// Anything still escrowed for the offer is returned to the buyer.
+fn close_offer(env: &Env, offer: &Offer) -> Result<(), Error> {
+    let escrowed = get_offer_escrow(env, offer.offer_id);
+    if escrowed > 0 {
+        set_offer_escrow(env, offer.offer_id, 0);
+        transfer_payment(
+            env,
+            &get_xlm_token_address(env),
+            &env.current_contract_address(),
+            &offer.buyer,
+            escrowed,
+        )?;
+    }
+
+    remove_offer(env, offer.offer_id);
+    remove_open_offer(env, offer);
+    remove_offer_thread(env, offer.offer_id);
+
+    Ok(())
+}
+
+/// Calculate fee based on basis points
+fn calculate_fee(amount: i128, fee_bps: u32) -> i128 {
+    (amount * fee_bps as i128) / 10000
+}
+
+/// Seller-specific fee adjustments: volume tier cap and membership discount
+struct SellerFees {
+    /// Fee cap from the highest tier the seller has reached
+    tier_fee_bps: Option<u32>,
+    /// Discount (bps of the fee) for holders of the membership NFT
+    discount_bps: u32,
+}
+
+impl SellerFees {
+    fn load(env: &Env, seller: &Address) -> Self {
+        let sales = get_seller_sales(env, seller);
+        let tier_fee_bps = get_fee_tiers(env)
+            .iter()
+            .filter(|tier| sales >= tier.min_sales)
+            .last()
+            .map(|tier| tier.fee_bps);
+
+        let discount_bps = match get_membership_discount(env) {
+            Some((membership_nft, discount_bps)) if holds_nft(env, &membership_nft, seller) => discount_bps,
+            _ => 0,
+        };
+
+        SellerFees {
+            tier_fee_bps,
+            discount_bps,
+        }
+    }
+
+    /// Platform fee for one item: the collection fee it settles at, capped
+    /// by the seller's tier, then discounted for members
+    fn fee_bps(&self, collection_fee_bps: u32) -> u32 {
+        let mut fee_bps = collection_fee_bps;
+
+        if let Some(tier_fee_bps) = self.tier_fee_bps {
+            fee_bps = fee_bps.min(tier_fee_bps);
+        }
+
+        fee_bps - fee_bps * self.discount_bps / 10000
+    }
+}
+
+/// Platform fee currently in effect for a collection: its override, or the
+/// global fee
+fn collection_fee_bps(env: &Env, nft_contract: &Address) -> u32 {
+    get_collection_fee(env, nft_contract).unwrap_or_else(|| get_platform_fee(env))
+}
+
+/// Platform fee currently in effect for each item, for sales that don't
+/// lock their fee (offers and signed orders)
+fn current_fees(env: &Env, items: &Vec<(Address, u64)>) -> Vec<u32> {
+    let mut fees = Vec::new(env);
+    for (nft_contract, _) in items.iter() {
+        fees.push_back(collection_fee_bps(env, &nft_contract));
+    }
+    fees
+}
+
+/// Whether `owner` holds at least one token of `nft_contract`
+/// Contracts without `balance_of` count as not held
+fn holds_nft(env: &Env, nft_contract: &Address, owner: &Address) -> bool {
+    use nft_interface::NFTContractClient;
+
+    matches!(
+        NFTContractClient::new(env, nft_contract).try_balance_of(owner),
+        Ok(Ok(balance)) if balance > 0
+    )
+}
+
+/// Reject blocked collections, and unregistered ones in allowlist-only mode
+fn check_collection_tradable(env: &Env, nft_contract: &Address) -> Result<(), Error> {
+    match get_collection_status(env, nft_contract) {
+        Some(CollectionStatus::Blocked) => Err(Error::CollectionBlocked),
+        Some(_) => Ok(()),
+        None if get_trading_mode(env) == TradingMode::AllowlistOnly => {
+            Err(Error::CollectionNotAllowed)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Compute the platform fee, royalties and seller proceeds for a sale
+///
+/// For bundles the price is shared equally between items (the last item
+/// takes the rounding remainder) and each item's royalty is computed on
+/// its share. `platform_fee_bps` holds the fee each item settles at,
+/// collection overrides included (locked on listings, auctions and
+/// raffles). Fails with `InvalidRoyalty` if a collection reports an
+/// out-of-range royalty.
+fn compute_sale_split(
+    env: &Env,
+    items: &Vec<(Address, u64)>,
+    price: i128,
+    seller: &Address,
+    platform_fee_bps: &Vec<u32>,
+    referrer: &Option<Address>,
+) -> Result<FeeQuote, Error> {
+    let seller_fees = SellerFees::load(env, seller);
+
+    // Get platform fee and royalty info per item (if available)
+    let count = items.len() as i128;
+    let item_share = price / count;
+    let mut platform_fee: i128 = 0;
+    let mut royalties = Vec::new(env);
+    let mut royalty_amount: i128 = 0;
+
+    for (index, (nft_contract, token_id)) in items.iter().enumerate() {
+        let share = if index as i128 == count - 1 {
+            price - item_share * (count - 1)
+        } else {
+            item_share
+        };
+
+        let item_fee_bps = platform_fee_bps.get(index as u32).unwrap_or(0);
+        platform_fee += calculate_fee(share, seller_fees.fee_bps(item_fee_bps));
+
+        for (recipient, amount) in get_royalty_info(env, &nft_contract, token_id, share)?.iter() {
+            royalties.push_back((recipient, amount));
+            royalty_amount += amount;
+        }
+    }
+
+    // Registered referrers receive a share of the platform fee
+    let referrer = referrer
+        .clone()
+        .filter(|referrer| is_referrer_registered(env, referrer));
+    let referral_fee = match &referrer {
+        Some(referrer) => calculate_fee(platform_fee, get_referrer_share(env, referrer)),
+        None => 0,
+    };
+
+    // Calculate seller proceeds
+    let seller_proceeds = price - platform_fee - royalty_amount;
+
+    Ok(FeeQuote {
+        platform_fee,
+        referrer,
+        referral_fee,
+        royalties,
+        royalty_amount,
+        seller_proceeds,
+    })
+}
+
+/// Check a listing is active and not expired
+fn check_listing_available(env: &Env, listing: &Listing) -> Result<(), Error> {
+    // Verify listing is active
+    if listing.status != ListingStatus::Active {
+        return Err(Error::ListingNotActive);
+    }
+
+    // Check expiry
+    if let Some(expiry) = listing.expires_at {
+        if env.ledger().timestamp() > expiry {
+            return Err(Error::ListingExpired);
+        }
+    }
+
+    Ok(())
+}
+
+/// Build a `SalePreview` with the same checks and split as `settle_sale`
+fn preview_sale(
+    env: &Env,
+    payment_token: Address,
+    items: &Vec<(Address, u64)>,
+    seller: &Address,
+    price: i128,
+    platform_fee_bps: &Vec<u32>,
+) -> Result<SalePreview, Error> {
+    // Settlement rejects these in transfer_nft
+    for (nft_contract, _) in items.iter() {
+        check_collection_tradable(env, &nft_contract)?;
+    }
+
+    Ok(SalePreview {
+        price,
+        payment_token,
+        fees: compute_sale_split(env, items, price, seller, platform_fee_bps, &None)?,
+    })
+}
+
+/// A sale to settle: what is sold, by whom, and at what price
+struct Sale {
+    payment_token: Address,
+    /// Pays the price: the buyer, or the marketplace for escrowed funds
+    payer: Address,
+    items: Vec<(Address, u64)>,
+    seller: Address,
+    price: i128,
+    /// Platform fee per item the sale settles at
+    platform_fee_bps: Vec<u32>,
+}
+
+/// Collect a sale payment from `sale.payer` and move the NFTs to `buyer`
+///
+/// Splits the price between platform fee, royalty recipients and seller,
+/// then transfers every item. Shared by listings, bundles, offers and
+/// signed orders so every settlement path pays out identically.
+/// Returns the fee breakdown that was paid.
+fn settle_sale(
+    env: &Env,
+    sale: &Sale,
+    buyer: &Address,
+    referrer: &Option<Address>,
+) -> Result<FeeQuote, Error> {
+    let split = compute_sale_split(
+        env,
+        &sale.items,
+        sale.price,
+        &sale.seller,
+        &sale.platform_fee_bps,
+        referrer,
+    )?;
+    increment_seller_sales(env, &sale.seller);
+
+    // Pay the seller; fees and royalties are pushed or credited per recipient
+    let mut payouts = Payouts::new(env);
+    payouts.add_sale(env, &sale.seller, &get_fee_recipient(env)?, &split);
+    payouts.pay(env, &sale.payment_token, &sale.payer)?;
+
+    // Transfer NFTs (call transfer_from on each NFT contract)
+    for (nft_contract, token_id) in sale.items.iter() {
+        transfer_nft(env, &nft_contract, &sale.seller, buyer, token_id)?;
+    }
+
+    Ok(split)
+}
+
+/// Fee split for a sale paid from funds the marketplace already holds
+///
+/// Reads only, so callers can check the split before delivering anything.
+fn escrowed_sale_split(
+    env: &Env,
+    nft_contract: &Address,
+    token_id: u64,
+    seller: &Address,
+    price: i128,
+    platform_fee_bps: u32,
+) -> Result<FeeQuote, Error> {
+    compute_sale_split(
+        env,
+        &Vec::from_array(env, [(nft_contract.clone(), token_id)]),
+        price,
+        seller,
+        &Vec::from_array(env, [platform_fee_bps]),
+        &None,
+    )
+}
+
+/// Pay out a sale from funds the marketplace already holds
+/// The caller delivers the NFT.
+fn pay_escrowed_sale(
+    env: &Env,
+    payment_token: &Address,
+    seller: &Address,
+    split: &FeeQuote,
+) -> Result<(), Error> {
+    increment_seller_sales(env, seller);
+
+    let mut payouts = Payouts::new(env);
+    payouts.add_sale(env, seller, &get_fee_recipient(env)?, split);
+    payouts.pay_from_escrow(env, payment_token);
+
+    Ok(())
+}
+
+/// Buy one listing as part of `batch_buy`
+///
+/// Moves the NFT and marks the listing sold, but only records payments in
+/// `payouts`; the caller pays the aggregated amounts afterwards.
+/// Returns the listing price.
+fn buy_in_batch(
+    env: &Env,
+    listing_id: u64,
+    buyer: &Address,
+    referrer: &Option<Address>,
+    fee_recipient: &Address,
+    budget: i128,
+    payouts: &mut Payouts,
+) -> Result<i128, Error> {
+    let mut listing = get_listing(env, listing_id)?;
+
+    // Expired listings stay indexed until `expire_listings` prunes them
+    validate_purchase(env, &listing, buyer)?;
+
+    if listing.price > budget {
+        return Err(Error::MaxTotalExceeded);
+    }
+
+    // Nothing is accounted until every item is known to be transferable, so
+    // a blocked collection or a token the seller moved or stopped approving
+    // only skips this listing
+    let items = listed_tokens(env, &listing);
+    for (nft_contract, token_id) in items.iter() {
+        check_collection_tradable(env, &nft_contract)?;
+        if !is_owned_and_approved(env, &nft_contract, token_id, &listing.seller) {
+            return Err(Error::NotTokenOwner);
+        }
+    }
+
+    let split = compute_sale_split(env, &items, listing.price, &listing.seller, &listing.platform_fee_bps, referrer)?;
+
+    // Mark sold now so duplicate IDs later in the batch are rejected, and
+    // before the transfers so they don't invalidate this listing. A failed
+    // transfer reverts the whole batch.
+    listing.status = ListingStatus::Sold;
+    save_listing(env, listing_id, &listing);
+    remove_active_listing(env, &listing);
+
+    for (nft_contract, token_id) in items.iter() {
+        transfer_nft(env, &nft_contract, &listing.seller, buyer, token_id)?;
+    }
+
+    // Charge the buyer only once every item has moved
+    increment_seller_sales(env, &listing.seller);
+    payouts.add_sale(env, &listing.seller, fee_recipient, &split);
+
+    events::emit_sale(env, &listing, buyer, &split);
+
+    Ok(listing.price)
+}
+
+/// Amounts a buyer owes, aggregated per recipient
+///
+/// Seller proceeds are always transferred directly. Platform fees and
+/// royalties follow the recipient's `PayoutMode`: pushed with the sale, or
+/// escrowed in the marketplace and credited to the recipient's ledger.
+struct Payouts {
+    push: Map<Address, i128>,
+    pull: Map<Address, i128>,
+}
+
+impl Payouts {
+    fn new(env: &Env) -> Self {
+        Payouts {
+            push: Map::new(env),
+            pull: Map::new(env),
+        }
+    }
+
+    fn add_proceeds(&mut self, seller: &Address, amount: i128) {
+        add_amount(&mut self.push, seller, amount);
+    }
+
+    /// Add everything owed for one sale: seller proceeds, the platform fee
+    /// (less the referral share), the referral share and royalties
+    fn add_sale(&mut self, env: &Env, seller: &Address, fee_recipient: &Address, split: &FeeQuote) {
+        self.add_proceeds(seller, split.seller_proceeds);
+        self.add_fee(env, fee_recipient, split.platform_fee - split.referral_fee);
+
+        if let Some(referrer) = &split.referrer {
+            self.add_fee(env, referrer, split.referral_fee);
+        }
+
+        for (recipient, amount) in split.royalties.iter() {
+            self.add_fee(env, &recipient, amount);
+        }
+    }
+
+    fn add_fee(&mut self, env: &Env, recipient: &Address, amount: i128) {
+        match get_payout_mode(env, recipient) {
+            PayoutMode::Push => add_amount(&mut self.push, recipient, amount),
+            PayoutMode::Pull => add_amount(&mut self.pull, recipient, amount),
+        }
+    }
+
+    /// Transfer push payouts, then escrow pull payouts in a single transfer
+    fn pay(&self, env: &Env, payment_token: &Address, payer: &Address) -> Result<(), Error> {
+        for (recipient, amount) in self.push.iter() {
+            transfer_payment(env, payment_token, payer, &recipient, amount)?;
+        }
+
+        let mut escrowed: i128 = 0;
+        for (recipient, amount) in self.pull.iter() {
+            credit_claimable(env, &recipient, payment_token, amount);
+            escrowed += amount;
+        }
+
+        // Escrowed payments are already held by the marketplace
+        if escrowed > 0 && *payer != env.current_contract_address() {
+            transfer_payment(env, payment_token, payer, &env.current_contract_address(), escrowed)?;
+        }
+
+        Ok(())
+    }
+
+    /// Pay out of funds the marketplace already holds
+    ///
+    /// A push payout the token refuses is credited like a pull payout, so an
+    /// escrowed sale can't be blocked by one of its recipients.
+    fn pay_from_escrow(&self, env: &Env, payment_token: &Address) {
+        let token_client = soroban_sdk::token::Client::new(env, payment_token);
+        let marketplace = env.current_contract_address();
+
+        for (recipient, amount) in self.push.iter() {
+            if !matches!(token_client.try_transfer(&marketplace, &recipient, &amount), Ok(Ok(()))) {
+                credit_claimable(env, &recipient, payment_token, amount);
+            }
+        }
+
+        for (recipient, amount) in self.pull.iter() {
+            credit_claimable(env, &recipient, payment_token, amount);
+        }
+    }
+}
+
+/// Add `amount` to what the marketplace holds for `recipient`
+fn credit_claimable(env: &Env, recipient: &Address, payment_token: &Address, amount: i128) {
+    let balance = get_claimable(env, recipient, payment_token);
+    set_claimable(env, recipient, payment_token, balance + amount);
+}
+
+/// Add `amount` to a recipient's entry
+fn add_amount(amounts: &mut Map<Address, i128>, recipient: &Address, amount: i128) {
+    if amount > 0 {
+        let current = amounts.get(recipient.clone()).unwrap_or(0);
+        amounts.set(recipient.clone(), current + amount);
+    }
+}
+
+/// Check a listing can be bought by `buyer` right now
+fn validate_purchase(env: &Env, listing: &Listing, buyer: &Address) -> Result<(), Error> {
+    check_listing_available(env, listing)?;
+
+    // Cannot buy your own listing
+    if listing.seller == *buyer {
+        return Err(Error::CannotBuyOwnListing);
+    }
+
+    // Private listings can only be filled by the reserved buyer
+    if let Some(reserved_buyer) = &listing.reserved_buyer {
+        if reserved_buyer != buyer {
+            return Err(Error::NotReservedBuyer);
+        }
+    }
+
+    Ok(())
+}
+
+/// Transfer a payment token (XLM or any Stellar Asset Contract) between accounts
+fn transfer_payment(
+    env: &Env,
+    payment_token: &Address,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), Error> {
+    use soroban_sdk::token;
+
+    let token_client = token::Client::new(env, payment_token);
+
+    // This will fail if the payer doesn't have enough balance or hasn't approved
+    token_client.transfer(from, to, &amount);
+
+    Ok(())
+}
+
+/// Whether `seller` still owns the token and the marketplace may move it
+/// Burned tokens or misbehaving contracts count as not owned
+fn is_owned_and_approved(
+    env: &Env,
+    nft_contract: &Address,
+    token_id: u64,
+    seller: &Address,
+) -> bool {
+    use nft_interface::NFTContractClient;
+
+    let nft_client = NFTContractClient::new(env, nft_contract);
+    let marketplace = env.current_contract_address();
+
+    match nft_client.try_owner_of(&token_id) {
+        Ok(Ok(owner)) if owner == *seller => {}
+        _ => return false,
+    }
+
+    let approved = matches!(
+        nft_client.try_get_approved(&token_id),
+        Ok(Ok(Some(spender))) if spender == marketplace
+    );
+
+    approved || matches!(
+        nft_client.try_is_approved_for_all(seller, &marketplace),
+        Ok(Ok(true))
+    )
+}
+
+/// Verify `owner` currently owns the token
+fn check_owner(
+    env: &Env,
+    nft_contract: &Address,
+    token_id: u64,
+    owner: &Address,
+) -> Result<(), Error> {
+    use nft_interface::NFTContractClient;
+
+    let nft_client = NFTContractClient::new(env, nft_contract);
+
+    if nft_client.owner_of(&token_id) != *owner {
+        return Err(Error::NotTokenOwner);
+    }
+
+    Ok(())
+}
+
+/// Verify `seller` owns the token and the marketplace may transfer it
+fn check_owner_and_approval(
+    env: &Env,
+    nft_contract: &Address,
+    token_id: u64,
+    seller: &Address,
+) -> Result<(), Error> {
+    use nft_interface::NFTContractClient;
+
+    check_owner(env, nft_contract, token_id, seller)?;
+
+    let nft_client = NFTContractClient::new(env, nft_contract);
+    let marketplace = env.current_contract_address();
+
+    let approved = nft_client.get_approved(&token_id) == Some(marketplace.clone());
+    if !approved && !nft_client.is_approved_for_all(seller, &marketplace) {
+        return Err(Error::MarketplaceNotApproved);
+    }
+
+    Ok(())
+}
+
+/// Transfer NFT via cross-contract call to NFT contract
+fn transfer_nft(
+    env: &Env,
+    nft_contract: &Address,
+    from: &Address,
+    to: &Address,
+    token_id: u64,
+) -> Result<(), Error> {
+    use nft_interface::NFTContractClient;
+
+    // Every sale and swap moves NFTs through here, so this also stops
+    // trades in collections blocked after the listing was created
+    check_collection_tradable(env, nft_contract)?;
+
+    // Create a client for the NFT contract using its interface
+    // We'll call transfer_from as the marketplace (authorized operator)
+    let nft_client = NFTContractClient::new(env, nft_contract);
+
+    // The marketplace contract must be approved to transfer this NFT
+    // This should have been done when the listing was created
+    match nft_client.try_transfer_from(
+        &env.current_contract_address(), // spender (this marketplace)
+        from,                             // current owner
+        to,                               // new owner
+        &token_id,
+    ) {
+        Ok(Ok(())) => {
+            // Offers, swaps, orders and auctions can move a token that is
+            // also listed; its listing can no longer be filled
+            invalidate_token_listing(env, nft_contract, token_id, from);
+            Ok(())
+        }
+        _ => Err(Error::TransferFailed),
+    }
+}
+
+/// Open a raffle, with validated terms, for a token the marketplace holds
+/// Shared by `create_raffle` and raffle deposits via `on_nft_received`.
+fn start_raffle(
+    env: &Env,
+    seller: &Address,
+    nft_contract: &Address,
+    token_id: u64,
+    terms: &RaffleTerms,
+) -> Result<u64, Error> {
+    let raffle_id = get_and_increment_raffle_counter(env);
+    let raffle = Raffle {
+        raffle_id,
+        nft_contract: nft_contract.clone(),
+        token_id,
+        seller: seller.clone(),
+        terms: terms.clone(),
+        status: RaffleStatus::Open,
+        tickets_sold: 0,
+        winner: None,
+        created_at: env.ledger().timestamp(),
+        platform_fee_bps: collection_fee_bps(env, nft_contract),
+    };
+
+    save_raffle(env, &raffle);
+
+    events::emit_raffle_created(env, raffle_id, nft_contract, token_id, seller, terms);
+
+    Ok(raffle_id)
+}
+
+/// Send a token the marketplace holds in escrow
+/// Uses the owner-authorized `transfer`; escrow release is not a trade, so
+/// collection checks don't apply.
+fn release_escrowed_nft(env: &Env, nft_contract: &Address, to: &Address, token_id: u64) {
+    use nft_interface::NFTContractClient;
+
+    NFTContractClient::new(env, nft_contract).transfer(&env.current_contract_address(), to, &token_id);
+}
+
+/// Get XLM token address for the current network
+/// This is the Stellar Asset Contract (SAC) address for native XLM
+fn get_xlm_token_address(env: &Env) -> Address {
+    // On Stellar, native XLM has a deterministic contract address
+    // This is computed from the Stellar Asset (native)
+    // For now, we'll store it in contract storage during initialization
+    storage::get_xlm_token_address(env)
+        .expect("XLM token address not configured")
+}
+
+/// Client interface for calling NFT contract functions
+mod nft_interface {
+    use soroban_sdk::{contractclient, Address, Env};
+
+    #[contractclient(name = "NFTContractClient")]
+    pub trait NFTContractInterface {
+        fn owner_of(env: Env, token_id: u64) -> Address;
+
+        fn get_approved(env: Env, token_id: u64) -> Option<Address>;
+
+        fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool;
+
+        fn balance_of(env: Env, owner: Address) -> u64;
+
+        /// Transfer an NFT the caller owns
+        fn transfer(env: Env, from: Address, to: Address, token_id: u64);
 
-    #[contractclient(name = "NFTContractClient")]
-    pub trait NFTContractInterface {
         /// Transfer NFT with approval (without Result for simplicity)
         fn transfer_from(
             env: Env,
@@ -0,0 +1,51 @@
+//! Off-chain signed orders (Seaport-style)
+//!
+//! Sellers sign an `Order` off chain instead of creating an on-chain listing.
+//! Buyers submit the order and signature to `fulfill_order`, which verifies
+//! the ed25519 signature against the seller's registered signing key.
+
+use soroban_sdk::{xdr::ToXdr, Bytes, BytesN, Env};
+use crate::{Order, OrderState, errors::Error, storage};
+
+// Domain separator: binds signatures to this marketplace contract
+const ORDER_DOMAIN: &[u8] = b"NunaCurateOrder";
+
+/// Hash an order for signing
+/// sha256(domain || marketplace address || order)
+pub fn hash_order(env: &Env, order: &Order) -> BytesN<32> {
+    let mut payload = Bytes::from_slice(env, ORDER_DOMAIN);
+    payload.append(&env.current_contract_address().to_xdr(env));
+    payload.append(&order.clone().to_xdr(env));
+
+    env.crypto().sha256(&payload).into()
+}
+
+/// Verify the seller's ed25519 signature over the order hash
+/// Traps (reverting the whole call) if the signature is invalid
+pub fn verify_order_signature(
+    env: &Env,
+    public_key: &BytesN<32>,
+    order_hash: &BytesN<32>,
+    signature: &BytesN<64>,
+) {
+    let message: Bytes = order_hash.clone().into();
+    env.crypto().ed25519_verify(public_key, &message, signature);
+}
+
+/// Check an order can still be filled: not expired, current nonce,
+/// and not already filled or cancelled
+pub fn check_order_fillable(env: &Env, order: &Order, order_hash: &BytesN<32>) -> Result<(), Error> {
+    if env.ledger().timestamp() > order.expires_at {
+        return Err(Error::OrderExpired);
+    }
+
+    if order.nonce != storage::get_seller_nonce(env, &order.seller) {
+        return Err(Error::InvalidOrderNonce);
+    }
+
+    match storage::get_order_state(env, order_hash) {
+        Some(OrderState::Filled) => Err(Error::OrderAlreadyFilled),
+        Some(OrderState::Cancelled) => Err(Error::OrderCancelled),
+        None => Ok(()),
+    }
+}
@@ -1,48 +1,87 @@
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{Address, Env, Vec};
+use crate::{errors::Error, storage::get_max_royalty_bps};
 
 /// Get royalty information from NFT contract
 ///
-/// Returns (recipient_address, royalty_amount)
-/// Follows ERC-2981 standard for royalties
+/// Returns every (recipient_address, royalty_amount) with a non-zero amount.
+/// Uses `royalty_splits` for collections with several recipients and falls
+/// back to the single-recipient ERC-2981 `royalty_info`.
+///
+/// The NFT contract is untrusted: a receiver that is the marketplace or the
+/// NFT contract itself, a negative amount, or a total above the
+/// marketplace's max royalty fails with `InvalidRoyalty`. Zero amounts, such
+/// as a share that rounds down on a low price, are skipped.
 pub fn get_royalty_info(
     env: &Env,
     nft_contract: &Address,
     token_id: u64,
     sale_price: i128,
-) -> (Address, i128) {
+) -> Result<Vec<(Address, i128)>, Error> {
     use royalty_interface::NFTRoyaltyClient;
 
     // Create client for NFT contract's royalty interface
     let royalty_client = NFTRoyaltyClient::new(env, nft_contract);
 
-    // Try to get royalty info, fallback to zero if not supported
-    // This allows backwards compatibility with NFTs without royalties
-    match royalty_client.try_royalty_info(&token_id, &sale_price) {
-        Ok(result) => result,
-        Err(_) => {
-            // NFT doesn't support royalties, return zero
-            let zero_address = env.current_contract_address();
-            (zero_address, 0)
+    let reported = match royalty_client.try_royalty_splits(&token_id, &sale_price) {
+        Ok(Ok(splits)) => splits,
+        _ => {
+            // Try to get royalty info, fallback to zero if not supported
+            // This allows backwards compatibility with NFTs without royalties
+            match royalty_client.try_royalty_info(&token_id, &sale_price) {
+                Ok(Ok(info)) => Vec::from_array(env, [info]),
+                _ => Vec::new(env),
+            }
+        }
+    };
+
+    let max_royalty = calculate_royalty(sale_price, get_max_royalty_bps(env));
+    let mut royalties = Vec::new(env);
+    let mut total: i128 = 0;
+
+    for (recipient, amount) in reported.iter() {
+        // ERC-2981 reports "no royalty" as a zero amount
+        if amount == 0 {
+            continue;
+        }
+
+        if amount < 0
+            || recipient == env.current_contract_address()
+            || recipient == *nft_contract
+        {
+            return Err(Error::InvalidRoyalty);
         }
+
+        total = total.checked_add(amount).ok_or(Error::InvalidRoyalty)?;
+        royalties.push_back((recipient, amount));
+    }
+
+    if total > max_royalty {
+        return Err(Error::InvalidRoyalty);
     }
+
+    Ok(royalties)
 }
 
 /// Interface for calling NFT contract royalty functions (ERC-2981)
 mod royalty_interface {
-    use soroban_sdk::{contractclient, Address, Env};
+    use soroban_sdk::{contractclient, Address, Env, Vec};
 
     #[contractclient(name = "NFTRoyaltyClient")]
     pub trait NFTRoyaltyInterface {
         /// Get royalty info for a token sale
         /// Returns (receiver address, royalty amount)
         fn royalty_info(env: Env, token_id: u64, sale_price: i128) -> (Address, i128);
+
+        /// Get every royalty receiver and amount for a token sale
+        fn royalty_splits(env: Env, token_id: u64, sale_price: i128) -> Vec<(Address, i128)>;
     }
 }
 
-/// Validate royalty percentage (in basis points)
-/// Max 10% = 1000 bps
+/// Validate the marketplace max royalty (in basis points)
+/// Max 90% = 9000 bps, so even with the max 10% platform fee the seller's
+/// proceeds can't go negative
 pub fn validate_royalty_bps(bps: u32) -> bool {
-    bps <= 1000
+    bps <= 9000
 }
 
 /// Calculate royalty amount from price and basis points
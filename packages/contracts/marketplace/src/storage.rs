@@ -1,5 +1,5 @@
-use soroban_sdk::{Address, Env};
-use crate::{Listing, Offer, errors::Error};
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+use crate::{CollectionStatus, CounterOffer, GovernanceConfig, Proposal, Raffle, ScheduledFeeChange, SealedAuction, SealedBid, FeeTier, Listing, Offer, OrderState, PayoutMode, PricePoint, Swap, TradingMode, errors::Error};
 
 // ============================================================================
 // TTL CONFIGURATION (Time To Live for State Archival)
@@ -33,10 +33,24 @@ const INSTANCE_BUMP_AMOUNT: u32 = 2_073_600;         // ~120 days
 // Storage keys
 const ADMIN_KEY: &str = "admin";
 const PLATFORM_FEE_KEY: &str = "fee";
+const FEE_CHANGE_KEY: &str = "fee_chg";
+const LAST_FEE_CHANGE_KEY: &str = "fee_chgat";
 const FEE_RECIPIENT_KEY: &str = "fee_rcpt";
+const MAX_ROYALTY_KEY: &str = "max_roy";
+const TRADING_MODE_KEY: &str = "trd_mode";
+const FEE_TIERS_KEY: &str = "fee_tiers";
+const MEMBERSHIP_KEY: &str = "member";
+const REFERRAL_SHARE_KEY: &str = "ref_share";
 const LISTING_COUNTER_KEY: &str = "list_cnt";
 const OFFER_COUNTER_KEY: &str = "offr_cnt";
 const XLM_TOKEN_KEY: &str = "xlm_token";
+const SWAP_COUNTER_KEY: &str = "swap_cnt";
+const COUNTER_OFFER_COUNTER_KEY: &str = "ctr_cnt";
+const AUCTION_COUNTER_KEY: &str = "auct_cnt";
+const RAFFLE_COUNTER_KEY: &str = "rfl_cnt";
+const GOVERNANCE_KEY: &str = "gov";
+const PROPOSAL_COUNTER_KEY: &str = "prop_cnt";
+const FIRST_CURRENT_PROPOSAL_KEY: &str = "prop_frst";
 
 fn listing_key(listing_id: u64) -> soroban_sdk::Symbol {
     soroban_sdk::symbol_short!("listing")
@@ -46,8 +60,112 @@ fn offer_key(offer_id: u64) -> soroban_sdk::Symbol {
     soroban_sdk::symbol_short!("offer")
 }
 
-fn active_listings_key() -> soroban_sdk::Symbol {
-    soroban_sdk::symbol_short!("act_list")
+fn counter_offer_key(_counter_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("ctr_offer")
+}
+
+fn offer_thread_key(_thread_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("ctr_thrd")
+}
+
+fn offer_escrow_key(_offer_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("offr_escr")
+}
+
+fn sealed_auction_key(_auction_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("seal_auct")
+}
+
+fn sealed_bid_key(_auction_id: u64, _bidder: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("seal_bid")
+}
+
+fn raffle_key(_raffle_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("raffle")
+}
+
+fn raffle_entries_key(_raffle_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("rfl_ent")
+}
+
+fn raffle_tickets_key(_raffle_id: u64, _buyer: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("rfl_tix")
+}
+
+fn proposal_key(_proposal_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("proposal")
+}
+
+fn swap_key(_swap_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("swap")
+}
+
+fn token_listing_key(_nft_contract: &Address, _token_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("tok_list")
+}
+
+fn index_len_key(_index: &IdIndex) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("idx_len")
+}
+
+fn index_bucket_key(_index: &IdIndex) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("idx_bkt")
+}
+
+fn index_pos_key(_index: &IdIndex) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("idx_pos")
+}
+
+fn price_history_key(_listing_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("price_hst")
+}
+
+fn order_signer_key(_seller: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("ord_key")
+}
+
+fn seller_nonce_key(_seller: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("ord_nonce")
+}
+
+fn order_state_key(_order_hash: &BytesN<32>) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("ord_state")
+}
+
+fn payout_mode_key(_recipient: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("pay_mode")
+}
+
+fn claimable_key(_recipient: &Address, _token: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("claimable")
+}
+
+fn collection_status_key(_nft_contract: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("coll_stat")
+}
+
+fn collection_fee_key(_nft_contract: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("coll_fee")
+}
+
+fn collection_fee_change_key(_nft_contract: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("cfee_next")
+}
+
+fn last_collection_fee_change_key(_nft_contract: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("cfee_last")
+}
+
+fn seller_sales_key(_seller: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("sell_cnt")
+}
+
+fn referrer_key(_referrer: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("ref_reg")
+}
+
+fn referrer_share_key(_referrer: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("ref_ovr")
 }
 
 // ============================================================================
@@ -84,6 +202,14 @@ fn extend_offer_ttl(env: &Env, offer_id: u64) {
         .extend_ttl(key, OFFER_LIFETIME_THRESHOLD, OFFER_BUMP_AMOUNT);
 }
 
+/// Extend TTL for swap storage (same lifetime as listings)
+fn extend_swap_ttl(env: &Env, swap_id: u64) {
+    let key = &(swap_key(swap_id), swap_id);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, LISTING_LIFETIME_THRESHOLD, LISTING_BUMP_AMOUNT);
+}
+
 /// Bump instance TTL on every contract call (called in initialization)
 pub fn bump_instance(env: &Env) {
     extend_instance_ttl(env);
@@ -112,13 +238,55 @@ pub fn set_platform_fee(env: &Env, fee_bps: u32) {
     env.storage().instance().set(&PLATFORM_FEE_KEY, &fee_bps);
 }
 
+/// Platform fee in effect, including a scheduled change that is due but
+/// not yet written back
 pub fn get_platform_fee(env: &Env) -> u32 {
+    if let Some(change) = get_scheduled_fee_change(env) {
+        if crate::fee_schedule::is_due(env, &change) {
+            return change.fee_bps;
+        }
+    }
+
     env.storage()
         .instance()
         .get(&PLATFORM_FEE_KEY)
         .unwrap_or(0)
 }
 
+pub fn get_scheduled_fee_change(env: &Env) -> Option<ScheduledFeeChange> {
+    env.storage().instance().get(&FEE_CHANGE_KEY)
+}
+
+/// None cancels the scheduled change
+pub fn set_scheduled_fee_change(env: &Env, change: &Option<ScheduledFeeChange>) {
+    match change {
+        Some(change) => env.storage().instance().set(&FEE_CHANGE_KEY, change),
+        None => env.storage().instance().remove(&FEE_CHANGE_KEY),
+    }
+}
+
+/// When the last scheduled change took effect
+pub fn get_last_fee_change(env: &Env) -> Option<u64> {
+    env.storage().instance().get(&LAST_FEE_CHANGE_KEY)
+}
+
+pub fn set_last_fee_change(env: &Env, effective_at: u64) {
+    env.storage().instance().set(&LAST_FEE_CHANGE_KEY, &effective_at);
+}
+
+pub fn set_max_royalty_bps(env: &Env, max_bps: u32) {
+    env.storage().instance().set(&MAX_ROYALTY_KEY, &max_bps);
+}
+
+/// Highest royalty (bps of the sale price) the marketplace will pay
+/// Defaults to 10%, the cap enforced by nuna-nft
+pub fn get_max_royalty_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&MAX_ROYALTY_KEY)
+        .unwrap_or(1000)
+}
+
 pub fn set_fee_recipient(env: &Env, recipient: &Address) {
     env.storage().instance().set(&FEE_RECIPIENT_KEY, recipient);
 }
@@ -170,6 +338,61 @@ pub fn get_and_increment_offer_counter(env: &Env) -> u64 {
     counter
 }
 
+pub fn get_and_increment_counter_offer_counter(env: &Env) -> u64 {
+    let counter: u64 = env.storage()
+        .instance()
+        .get(&COUNTER_OFFER_COUNTER_KEY)
+        .unwrap_or(0);
+
+    env.storage().instance().set(&COUNTER_OFFER_COUNTER_KEY, &(counter + 1));
+
+    counter
+}
+
+pub fn get_and_increment_auction_counter(env: &Env) -> u64 {
+    let counter: u64 = env.storage()
+        .instance()
+        .get(&AUCTION_COUNTER_KEY)
+        .unwrap_or(0);
+
+    env.storage().instance().set(&AUCTION_COUNTER_KEY, &(counter + 1));
+
+    counter
+}
+
+pub fn get_and_increment_raffle_counter(env: &Env) -> u64 {
+    let counter: u64 = env.storage()
+        .instance()
+        .get(&RAFFLE_COUNTER_KEY)
+        .unwrap_or(0);
+
+    env.storage().instance().set(&RAFFLE_COUNTER_KEY, &(counter + 1));
+
+    counter
+}
+
+pub fn get_and_increment_proposal_counter(env: &Env) -> u64 {
+    let counter: u64 = env.storage()
+        .instance()
+        .get(&PROPOSAL_COUNTER_KEY)
+        .unwrap_or(0);
+
+    env.storage().instance().set(&PROPOSAL_COUNTER_KEY, &(counter + 1));
+
+    counter
+}
+
+pub fn get_and_increment_swap_counter(env: &Env) -> u64 {
+    let counter: u64 = env.storage()
+        .instance()
+        .get(&SWAP_COUNTER_KEY)
+        .unwrap_or(0);
+
+    env.storage().instance().set(&SWAP_COUNTER_KEY, &(counter + 1));
+
+    counter
+}
+
 // ============================================================================
 // LISTINGS (with TTL management)
 // ============================================================================
@@ -207,38 +430,257 @@ pub fn remove_listing(env: &Env, listing_id: u64) {
         .remove(&(listing_key(listing_id), listing_id));
 }
 
-pub fn add_active_listing(env: &Env, listing_id: u64) {
-    let mut active: soroban_sdk::Vec<u64> = env.storage()
-        .instance()
-        .get(&active_listings_key())
-        .unwrap_or(soroban_sdk::Vec::new(&env));
+// ============================================================================
+// ACTIVE LISTING / OPEN OFFER INDEXES
+// ============================================================================
+// Each index is a list of listing or offer IDs split into fixed-size buckets
+// in persistent storage, so no single entry grows with marketplace volume.
+// Every ID also stores its position, allowing O(1) swap-and-pop removal
+// (same approach as the NFT contract's owner enumeration).
+// ============================================================================
 
-    active.push_back(listing_id);
+/// IDs per bucket
+const INDEX_BUCKET_SIZE: u32 = 100;
 
-    env.storage().instance().set(&active_listings_key(), &active);
+/// Which ID index an entry belongs to
+#[contracttype]
+#[derive(Clone)]
+pub enum IdIndex {
+    ActiveListings,
+    CollectionListings(Address),
+    SellerListings(Address),
+    /// Private listings reserved for a buyer
+    ReservedListings(Address),
+    TokenOffers(Address, u64),
+    BuyerOffers(Address),
+    OpenOffers,
 }
 
-pub fn remove_active_listing(env: &Env, listing_id: u64) {
-    let active: Option<soroban_sdk::Vec<u64>> = env.storage()
-        .instance()
-        .get(&active_listings_key());
+/// Indexes a listing appears in: global, its seller, its reserved buyer
+/// (if private), and every collection it contains
+fn listing_indexes(env: &Env, listing: &Listing) -> soroban_sdk::Vec<IdIndex> {
+    let mut indexes = soroban_sdk::Vec::new(env);
+    indexes.push_back(IdIndex::ActiveListings);
+    indexes.push_back(IdIndex::SellerListings(listing.seller.clone()));
+
+    if let Some(buyer) = &listing.reserved_buyer {
+        indexes.push_back(IdIndex::ReservedListings(buyer.clone()));
+    }
+
+    let mut collections: soroban_sdk::Vec<Address> = soroban_sdk::Vec::new(env);
+    for (nft_contract, _) in listed_tokens(env, listing).iter() {
+        if !collections.contains(&nft_contract) {
+            collections.push_back(nft_contract.clone());
+            indexes.push_back(IdIndex::CollectionListings(nft_contract));
+        }
+    }
+
+    indexes
+}
+
+/// Tokens covered by a listing: the bundle items, or the single listed token
+pub fn listed_tokens(env: &Env, listing: &Listing) -> soroban_sdk::Vec<(Address, u64)> {
+    if listing.bundle_items.is_empty() {
+        soroban_sdk::Vec::from_array(env, [(listing.nft_contract.clone(), listing.token_id)])
+    } else {
+        listing.bundle_items.clone()
+    }
+}
+
+/// Add a listing to the global, seller, reserved-buyer, collection and
+/// per-token indexes
+pub fn add_active_listing(env: &Env, listing: &Listing) {
+    for index in listing_indexes(env, listing).iter() {
+        index_add(env, &index, listing.listing_id);
+    }
+
+    for (nft_contract, token_id) in listed_tokens(env, listing).iter() {
+        let key = &(token_listing_key(&nft_contract, token_id), nft_contract, token_id);
+        env.storage().persistent().set(key, &listing.listing_id);
+        extend_persistent_ttl(env, key);
+    }
+}
+
+pub fn remove_active_listing(env: &Env, listing: &Listing) {
+    for index in listing_indexes(env, listing).iter() {
+        index_remove(env, &index, listing.listing_id);
+    }
+
+    for (nft_contract, token_id) in listed_tokens(env, listing).iter() {
+        // Only clear the token entry if a newer listing hasn't replaced it
+        if get_token_listing(env, &nft_contract, token_id) == Some(listing.listing_id) {
+            env.storage()
+                .persistent()
+                .remove(&(token_listing_key(&nft_contract, token_id), nft_contract, token_id));
+        }
+    }
+}
+
+/// Active listing covering a token, if any
+pub fn get_token_listing(env: &Env, nft_contract: &Address, token_id: u64) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&(token_listing_key(nft_contract, token_id), nft_contract.clone(), token_id))
+}
+
+/// Add an offer to the global, per-token and per-buyer indexes
+pub fn add_open_offer(env: &Env, offer: &Offer) {
+    index_add(env, &IdIndex::OpenOffers, offer.offer_id);
+    index_add(env, &IdIndex::TokenOffers(offer.nft_contract.clone(), offer.token_id), offer.offer_id);
+    index_add(env, &IdIndex::BuyerOffers(offer.buyer.clone()), offer.offer_id);
+}
+
+pub fn remove_open_offer(env: &Env, offer: &Offer) {
+    index_remove(env, &IdIndex::OpenOffers, offer.offer_id);
+    index_remove(env, &IdIndex::TokenOffers(offer.nft_contract.clone(), offer.token_id), offer.offer_id);
+    index_remove(env, &IdIndex::BuyerOffers(offer.buyer.clone()), offer.offer_id);
+}
+
+/// Number of IDs in an index
+pub fn index_len(env: &Env, index: &IdIndex) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(index_len_key(index), index.clone()))
+        .unwrap_or(0)
+}
+
+/// Get a page of IDs from an index
+/// Returns (ids, total_count)
+pub fn index_page(
+    env: &Env,
+    index: &IdIndex,
+    offset: u32,
+    limit: u32,
+) -> (soroban_sdk::Vec<u64>, u32) {
+    let total_count = index_len(env, index);
+    let start = offset.min(total_count);
+    let end = offset.saturating_add(limit).min(total_count);
+
+    let mut result = soroban_sdk::Vec::new(env);
+    let mut bucket_no = u32::MAX;
+    let mut bucket = soroban_sdk::Vec::new(env);
+
+    for position in start..end {
+        if position / INDEX_BUCKET_SIZE != bucket_no {
+            bucket_no = position / INDEX_BUCKET_SIZE;
+            bucket = get_index_bucket(env, index, bucket_no);
+        }
+        result.push_back(bucket.get(position % INDEX_BUCKET_SIZE).unwrap());
+    }
+
+    (result, total_count)
+}
+
+fn set_index_len(env: &Env, index: &IdIndex, len: u32) {
+    let key = &(index_len_key(index), index.clone());
+    if len == 0 {
+        env.storage().persistent().remove(key);
+    } else {
+        env.storage().persistent().set(key, &len);
+        extend_persistent_ttl(env, key);
+    }
+}
+
+fn get_index_bucket(env: &Env, index: &IdIndex, bucket_no: u32) -> soroban_sdk::Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&(index_bucket_key(index), index.clone(), bucket_no))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+fn set_index_bucket(env: &Env, index: &IdIndex, bucket_no: u32, ids: &soroban_sdk::Vec<u64>) {
+    let key = &(index_bucket_key(index), index.clone(), bucket_no);
+    if ids.is_empty() {
+        env.storage().persistent().remove(key);
+    } else {
+        env.storage().persistent().set(key, ids);
+        extend_persistent_ttl(env, key);
+    }
+}
+
+fn set_index_position(env: &Env, index: &IdIndex, id: u64, position: u32) {
+    let key = &(index_pos_key(index), index.clone(), id);
+    env.storage().persistent().set(key, &position);
+    extend_persistent_ttl(env, key);
+}
+
+fn index_add(env: &Env, index: &IdIndex, id: u64) {
+    let position = index_len(env, index);
+    let bucket_no = position / INDEX_BUCKET_SIZE;
+
+    let mut bucket = get_index_bucket(env, index, bucket_no);
+    bucket.push_back(id);
+    set_index_bucket(env, index, bucket_no, &bucket);
 
-    if let Some(mut listings) = active {
-        // Find and remove the listing_id
-        let mut new_listings = soroban_sdk::Vec::new(&env);
+    set_index_position(env, index, id, position);
+    set_index_len(env, index, position + 1);
+}
+
+fn index_remove(env: &Env, index: &IdIndex, id: u64) {
+    let pos_key = &(index_pos_key(index), index.clone(), id);
+    let position: Option<u32> = env.storage().persistent().get(pos_key);
+
+    if let Some(position) = position {
+        let last_position = index_len(env, index) - 1;
+        let last_bucket_no = last_position / INDEX_BUCKET_SIZE;
+        let mut last_bucket = get_index_bucket(env, index, last_bucket_no);
+
+        if position != last_position {
+            // Move the last ID into the removed slot
+            let last_id = last_bucket.get(last_position % INDEX_BUCKET_SIZE).unwrap();
+            let bucket_no = position / INDEX_BUCKET_SIZE;
 
-        for i in 0..listings.len() {
-            if let Some(id) = listings.get(i) {
-                if id != listing_id {
-                    new_listings.push_back(id);
-                }
+            if bucket_no == last_bucket_no {
+                last_bucket.set(position % INDEX_BUCKET_SIZE, last_id);
+            } else {
+                let mut bucket = get_index_bucket(env, index, bucket_no);
+                bucket.set(position % INDEX_BUCKET_SIZE, last_id);
+                set_index_bucket(env, index, bucket_no, &bucket);
             }
+
+            set_index_position(env, index, last_id, position);
         }
 
-        env.storage().instance().set(&active_listings_key(), &new_listings);
+        // Remove the last slot
+        last_bucket.pop_back();
+        set_index_bucket(env, index, last_bucket_no, &last_bucket);
+
+        env.storage().persistent().remove(pos_key);
+        set_index_len(env, index, last_position);
     }
 }
 
+/// Get a listing's price history (oldest first)
+pub fn get_price_history(env: &Env, listing_id: u64) -> soroban_sdk::Vec<PricePoint> {
+    env.storage()
+        .persistent()
+        .get(&(price_history_key(listing_id), listing_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+/// Delete a listing's price history
+pub fn remove_price_history(env: &Env, listing_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&(price_history_key(listing_id), listing_id));
+}
+
+/// Append the current price to a listing's history
+pub fn add_price_point(env: &Env, listing_id: u64, price: i128) {
+    let key = &(price_history_key(listing_id), listing_id);
+    let mut history = get_price_history(env, listing_id);
+
+    history.push_back(PricePoint {
+        price,
+        timestamp: env.ledger().timestamp(),
+    });
+
+    env.storage().persistent().set(key, &history);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, LISTING_LIFETIME_THRESHOLD, LISTING_BUMP_AMOUNT);
+}
+
 // ============================================================================
 // OFFERS (with TTL management)
 // ============================================================================
@@ -275,3 +717,550 @@ pub fn remove_offer(env: &Env, offer_id: u64) {
         .persistent()
         .remove(&(offer_key(offer_id), offer_id));
 }
+
+// ============================================================================
+// COUNTER-OFFERS (same lifetime as offers)
+// ============================================================================
+
+pub fn save_counter_offer(env: &Env, counter: &CounterOffer) {
+    let key = &(counter_offer_key(counter.counter_id), counter.counter_id);
+    env.storage().persistent().set(key, counter);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, OFFER_LIFETIME_THRESHOLD, OFFER_BUMP_AMOUNT);
+}
+
+pub fn get_counter_offer(env: &Env, counter_id: u64) -> Result<CounterOffer, Error> {
+    let key = &(counter_offer_key(counter_id), counter_id);
+    let counter = env.storage()
+        .persistent()
+        .get(key)
+        .ok_or(Error::CounterOfferNotFound)?;
+
+    env.storage()
+        .persistent()
+        .extend_ttl(key, OFFER_LIFETIME_THRESHOLD, OFFER_BUMP_AMOUNT);
+
+    Ok(counter)
+}
+
+pub fn remove_counter_offer(env: &Env, counter_id: u64) {
+    env.storage()
+        .persistent()
+        .remove(&(counter_offer_key(counter_id), counter_id));
+}
+
+/// Counter-offer IDs made in a negotiation thread (thread ID = original offer ID)
+pub fn get_offer_thread(env: &Env, thread_id: u64) -> soroban_sdk::Vec<u64> {
+    env.storage()
+        .persistent()
+        .get(&(offer_thread_key(thread_id), thread_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn add_to_offer_thread(env: &Env, thread_id: u64, counter_id: u64) {
+    let key = &(offer_thread_key(thread_id), thread_id);
+    let mut counters = get_offer_thread(env, thread_id);
+    counters.push_back(counter_id);
+
+    env.storage().persistent().set(key, &counters);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, OFFER_LIFETIME_THRESHOLD, OFFER_BUMP_AMOUNT);
+}
+
+pub fn remove_from_offer_thread(env: &Env, thread_id: u64, counter_id: u64) {
+    let mut counters = get_offer_thread(env, thread_id);
+    if let Some(index) = counters.first_index_of(counter_id) {
+        counters.remove(index);
+        set_offer_thread(env, thread_id, &counters);
+    }
+}
+
+fn set_offer_thread(env: &Env, thread_id: u64, counters: &soroban_sdk::Vec<u64>) {
+    let key = &(offer_thread_key(thread_id), thread_id);
+    if counters.is_empty() {
+        env.storage().persistent().remove(key);
+    } else {
+        env.storage().persistent().set(key, counters);
+    }
+}
+
+/// Delete every counter-offer in a thread along with the thread itself
+pub fn remove_offer_thread(env: &Env, thread_id: u64) {
+    for counter_id in get_offer_thread(env, thread_id).iter() {
+        remove_counter_offer(env, counter_id);
+    }
+    env.storage()
+        .persistent()
+        .remove(&(offer_thread_key(thread_id), thread_id));
+}
+
+/// Amount escrowed for an offer by accepted counter-offers
+pub fn get_offer_escrow(env: &Env, offer_id: u64) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&(offer_escrow_key(offer_id), offer_id))
+        .unwrap_or(0)
+}
+
+pub fn set_offer_escrow(env: &Env, offer_id: u64, amount: i128) {
+    let key = &(offer_escrow_key(offer_id), offer_id);
+    if amount == 0 {
+        env.storage().persistent().remove(key);
+    } else {
+        env.storage().persistent().set(key, &amount);
+        env.storage()
+            .persistent()
+            .extend_ttl(key, OFFER_LIFETIME_THRESHOLD, OFFER_BUMP_AMOUNT);
+    }
+}
+
+// ============================================================================
+// SEALED-BID AUCTIONS (same lifetime as listings)
+// ============================================================================
+
+pub fn save_sealed_auction(env: &Env, auction: &SealedAuction) {
+    let key = &(sealed_auction_key(auction.auction_id), auction.auction_id);
+    env.storage().persistent().set(key, auction);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, LISTING_LIFETIME_THRESHOLD, LISTING_BUMP_AMOUNT);
+}
+
+pub fn get_sealed_auction(env: &Env, auction_id: u64) -> Result<SealedAuction, Error> {
+    let key = &(sealed_auction_key(auction_id), auction_id);
+    let auction = env.storage()
+        .persistent()
+        .get(key)
+        .ok_or(Error::AuctionNotFound)?;
+
+    env.storage()
+        .persistent()
+        .extend_ttl(key, LISTING_LIFETIME_THRESHOLD, LISTING_BUMP_AMOUNT);
+
+    Ok(auction)
+}
+
+/// Bids hold escrowed funds, so they get the persistent lifetime
+pub fn save_sealed_bid(env: &Env, auction_id: u64, bidder: &Address, bid: &SealedBid) {
+    let key = &(sealed_bid_key(auction_id, bidder), auction_id, bidder.clone());
+    env.storage().persistent().set(key, bid);
+    extend_persistent_ttl(env, key);
+}
+
+pub fn get_sealed_bid(env: &Env, auction_id: u64, bidder: &Address) -> Option<SealedBid> {
+    env.storage()
+        .persistent()
+        .get(&(sealed_bid_key(auction_id, bidder), auction_id, bidder.clone()))
+}
+
+pub fn remove_sealed_bid(env: &Env, auction_id: u64, bidder: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&(sealed_bid_key(auction_id, bidder), auction_id, bidder.clone()));
+}
+
+// ============================================================================
+// RAFFLES (escrowed funds and NFTs: persistent lifetime)
+// ============================================================================
+
+pub fn save_raffle(env: &Env, raffle: &Raffle) {
+    let key = &(raffle_key(raffle.raffle_id), raffle.raffle_id);
+    env.storage().persistent().set(key, raffle);
+    extend_persistent_ttl(env, key);
+}
+
+pub fn get_raffle(env: &Env, raffle_id: u64) -> Result<Raffle, Error> {
+    let key = &(raffle_key(raffle_id), raffle_id);
+    let raffle = env.storage()
+        .persistent()
+        .get(key)
+        .ok_or(Error::RaffleNotFound)?;
+
+    extend_persistent_ttl(env, key);
+
+    Ok(raffle)
+}
+
+/// (buyer, running ticket total) per purchase, in purchase order
+pub fn get_raffle_entries(env: &Env, raffle_id: u64) -> soroban_sdk::Vec<(Address, u32)> {
+    env.storage()
+        .persistent()
+        .get(&(raffle_entries_key(raffle_id), raffle_id))
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+/// Record a ticket purchase; `total` is the raffle's ticket count after it
+pub fn add_raffle_entry(env: &Env, raffle_id: u64, buyer: &Address, count: u32, total: u32) {
+    let entries_key = &(raffle_entries_key(raffle_id), raffle_id);
+    let mut entries = get_raffle_entries(env, raffle_id);
+    entries.push_back((buyer.clone(), total));
+    env.storage().persistent().set(entries_key, &entries);
+    extend_persistent_ttl(env, entries_key);
+
+    let tickets_key = &(raffle_tickets_key(raffle_id, buyer), raffle_id, buyer.clone());
+    let tickets = get_raffle_tickets(env, raffle_id, buyer) + count;
+    env.storage().persistent().set(tickets_key, &tickets);
+    extend_persistent_ttl(env, tickets_key);
+}
+
+pub fn get_raffle_tickets(env: &Env, raffle_id: u64, buyer: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(raffle_tickets_key(raffle_id, buyer), raffle_id, buyer.clone()))
+        .unwrap_or(0)
+}
+
+pub fn remove_raffle_tickets(env: &Env, raffle_id: u64, buyer: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&(raffle_tickets_key(raffle_id, buyer), raffle_id, buyer.clone()));
+}
+
+// ============================================================================
+// GOVERNANCE
+// ============================================================================
+
+pub fn get_governance(env: &Env) -> Option<GovernanceConfig> {
+    env.storage().instance().get(&GOVERNANCE_KEY)
+}
+
+/// None disables governance
+/// Proposals made before the change are cancelled (see `get_proposal`).
+pub fn set_governance(env: &Env, config: &Option<GovernanceConfig>) {
+    match config {
+        Some(config) => env.storage().instance().set(&GOVERNANCE_KEY, config),
+        None => env.storage().instance().remove(&GOVERNANCE_KEY),
+    }
+
+    let next_proposal_id: u64 = env.storage()
+        .instance()
+        .get(&PROPOSAL_COUNTER_KEY)
+        .unwrap_or(0);
+    env.storage().instance().set(&FIRST_CURRENT_PROPOSAL_KEY, &next_proposal_id);
+}
+
+pub fn save_proposal(env: &Env, proposal: &Proposal) {
+    let key = &(proposal_key(proposal.proposal_id), proposal.proposal_id);
+    env.storage().persistent().set(key, proposal);
+    extend_persistent_ttl(env, key);
+}
+
+pub fn get_proposal(env: &Env, proposal_id: u64) -> Result<Proposal, Error> {
+    let key = &(proposal_key(proposal_id), proposal_id);
+    let mut proposal: Proposal = env.storage()
+        .persistent()
+        .get(key)
+        .ok_or(Error::InvalidProposal)?;
+
+    let first_current_id: u64 = env.storage()
+        .instance()
+        .get(&FIRST_CURRENT_PROPOSAL_KEY)
+        .unwrap_or(0);
+    proposal.status = nuna_governance::current_status(proposal.status, proposal_id, first_current_id);
+
+    extend_persistent_ttl(env, key);
+
+    Ok(proposal)
+}
+
+// ============================================================================
+// SWAPS (with TTL management)
+// ============================================================================
+
+pub fn save_swap(env: &Env, swap_id: u64, swap: &Swap) {
+    env.storage()
+        .persistent()
+        .set(&(swap_key(swap_id), swap_id), swap);
+
+    extend_swap_ttl(env, swap_id);
+}
+
+pub fn get_swap(env: &Env, swap_id: u64) -> Result<Swap, Error> {
+    let swap = env.storage()
+        .persistent()
+        .get(&(swap_key(swap_id), swap_id))
+        .ok_or(Error::SwapNotFound)?;
+
+    extend_swap_ttl(env, swap_id);
+
+    Ok(swap)
+}
+
+// ============================================================================
+// SIGNED ORDERS
+// ============================================================================
+
+/// Register the ed25519 public key a seller signs orders with
+pub fn set_order_signer(env: &Env, seller: &Address, public_key: &BytesN<32>) {
+    let key = &(order_signer_key(seller), seller);
+    env.storage().persistent().set(key, public_key);
+    extend_persistent_ttl(env, key);
+}
+
+pub fn get_order_signer(env: &Env, seller: &Address) -> Option<BytesN<32>> {
+    env.storage()
+        .persistent()
+        .get(&(order_signer_key(seller), seller))
+}
+
+/// Current order nonce for a seller; orders signed with an older nonce are void
+pub fn get_seller_nonce(env: &Env, seller: &Address) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&(seller_nonce_key(seller), seller))
+        .unwrap_or(0)
+}
+
+pub fn increment_seller_nonce(env: &Env, seller: &Address) -> u64 {
+    let key = &(seller_nonce_key(seller), seller);
+    let nonce = get_seller_nonce(env, seller) + 1;
+
+    env.storage().persistent().set(key, &nonce);
+    extend_persistent_ttl(env, key);
+
+    nonce
+}
+
+/// Record that an order was filled or cancelled (keyed by order hash)
+pub fn set_order_state(env: &Env, order_hash: &BytesN<32>, state: &OrderState) {
+    let key = &(order_state_key(order_hash), order_hash.clone());
+    env.storage().persistent().set(key, state);
+    extend_persistent_ttl(env, key);
+}
+
+pub fn get_order_state(env: &Env, order_hash: &BytesN<32>) -> Option<OrderState> {
+    env.storage()
+        .persistent()
+        .get(&(order_state_key(order_hash), order_hash.clone()))
+}
+
+// ============================================================================
+// PAYOUT LEDGER
+// ============================================================================
+
+/// How a recipient is paid fees and royalties (defaults to `Pull`)
+pub fn get_payout_mode(env: &Env, recipient: &Address) -> PayoutMode {
+    env.storage()
+        .persistent()
+        .get(&(payout_mode_key(recipient), recipient.clone()))
+        .unwrap_or(PayoutMode::Push)
+}
+
+pub fn set_payout_mode(env: &Env, recipient: &Address, mode: PayoutMode) {
+    let key = &(payout_mode_key(recipient), recipient.clone());
+    env.storage().persistent().set(key, &mode);
+    extend_persistent_ttl(env, key);
+}
+
+/// Amount of `token` held by the marketplace on behalf of a recipient
+pub fn get_claimable(env: &Env, recipient: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&(claimable_key(recipient, token), recipient.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_claimable(env: &Env, recipient: &Address, token: &Address, amount: i128) {
+    let key = &(claimable_key(recipient, token), recipient.clone(), token.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(key);
+    } else {
+        env.storage().persistent().set(key, &amount);
+        extend_persistent_ttl(env, key);
+    }
+}
+
+// ============================================================================
+// COLLECTION REGISTRY
+// ============================================================================
+
+/// Whether unregistered collections can be traded
+pub fn get_trading_mode(env: &Env) -> TradingMode {
+    env.storage()
+        .instance()
+        .get(&TRADING_MODE_KEY)
+        .unwrap_or(TradingMode::Open)
+}
+
+pub fn set_trading_mode(env: &Env, mode: TradingMode) {
+    env.storage().instance().set(&TRADING_MODE_KEY, &mode);
+}
+
+/// Registry status of a collection (None if never registered)
+pub fn get_collection_status(env: &Env, nft_contract: &Address) -> Option<CollectionStatus> {
+    env.storage()
+        .persistent()
+        .get(&(collection_status_key(nft_contract), nft_contract.clone()))
+}
+
+pub fn set_collection_status(env: &Env, nft_contract: &Address, status: &Option<CollectionStatus>) {
+    let key = &(collection_status_key(nft_contract), nft_contract.clone());
+    match status {
+        Some(status) => {
+            env.storage().persistent().set(key, status);
+            extend_persistent_ttl(env, key);
+        }
+        None => env.storage().persistent().remove(key),
+    }
+}
+
+/// Platform fee override for a collection (bps), including a scheduled
+/// change that is due but not yet written back
+pub fn get_collection_fee(env: &Env, nft_contract: &Address) -> Option<u32> {
+    if let Some(change) = get_scheduled_collection_fee_change(env, nft_contract) {
+        if crate::fee_schedule::is_due(env, &change) {
+            return Some(change.fee_bps);
+        }
+    }
+
+    env.storage()
+        .persistent()
+        .get(&(collection_fee_key(nft_contract), nft_contract.clone()))
+}
+
+pub fn set_collection_fee(env: &Env, nft_contract: &Address, fee_bps: Option<u32>) {
+    let key = &(collection_fee_key(nft_contract), nft_contract.clone());
+    match fee_bps {
+        Some(fee_bps) => {
+            env.storage().persistent().set(key, &fee_bps);
+            extend_persistent_ttl(env, key);
+        }
+        None => env.storage().persistent().remove(key),
+    }
+}
+
+pub fn get_scheduled_collection_fee_change(env: &Env, nft_contract: &Address) -> Option<ScheduledFeeChange> {
+    env.storage()
+        .persistent()
+        .get(&(collection_fee_change_key(nft_contract), nft_contract.clone()))
+}
+
+/// None cancels the scheduled change
+pub fn set_scheduled_collection_fee_change(
+    env: &Env,
+    nft_contract: &Address,
+    change: &Option<ScheduledFeeChange>,
+) {
+    let key = &(collection_fee_change_key(nft_contract), nft_contract.clone());
+    match change {
+        Some(change) => {
+            env.storage().persistent().set(key, change);
+            extend_persistent_ttl(env, key);
+        }
+        None => env.storage().persistent().remove(key),
+    }
+}
+
+/// When the collection's last scheduled change took effect
+pub fn get_last_collection_fee_change(env: &Env, nft_contract: &Address) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&(last_collection_fee_change_key(nft_contract), nft_contract.clone()))
+}
+
+pub fn set_last_collection_fee_change(env: &Env, nft_contract: &Address, effective_at: u64) {
+    let key = &(last_collection_fee_change_key(nft_contract), nft_contract.clone());
+    env.storage().persistent().set(key, &effective_at);
+    extend_persistent_ttl(env, key);
+}
+
+// ============================================================================
+// FEE TIERS
+// ============================================================================
+
+/// Seller volume tiers, ordered by ascending `min_sales`
+pub fn get_fee_tiers(env: &Env) -> soroban_sdk::Vec<FeeTier> {
+    env.storage()
+        .instance()
+        .get(&FEE_TIERS_KEY)
+        .unwrap_or(soroban_sdk::Vec::new(env))
+}
+
+pub fn set_fee_tiers(env: &Env, tiers: &soroban_sdk::Vec<FeeTier>) {
+    env.storage().instance().set(&FEE_TIERS_KEY, tiers);
+}
+
+/// Membership NFT contract and the fee discount (bps of the fee) its holders get
+pub fn get_membership_discount(env: &Env) -> Option<(Address, u32)> {
+    env.storage().instance().get(&MEMBERSHIP_KEY)
+}
+
+pub fn set_membership_discount(env: &Env, membership: &Option<(Address, u32)>) {
+    match membership {
+        Some(membership) => env.storage().instance().set(&MEMBERSHIP_KEY, membership),
+        None => env.storage().instance().remove(&MEMBERSHIP_KEY),
+    }
+}
+
+/// Number of sales a seller has completed on the marketplace
+pub fn get_seller_sales(env: &Env, seller: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(seller_sales_key(seller), seller.clone()))
+        .unwrap_or(0)
+}
+
+pub fn increment_seller_sales(env: &Env, seller: &Address) {
+    let key = &(seller_sales_key(seller), seller.clone());
+    let sales = get_seller_sales(env, seller).saturating_add(1);
+
+    env.storage().persistent().set(key, &sales);
+    extend_persistent_ttl(env, key);
+}
+
+// ============================================================================
+// REFERRALS
+// ============================================================================
+
+/// Default share of the platform fee paid to referrers (bps of the fee)
+pub fn get_referral_share(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&REFERRAL_SHARE_KEY)
+        .unwrap_or(0)
+}
+
+pub fn set_referral_share(env: &Env, share_bps: u32) {
+    env.storage().instance().set(&REFERRAL_SHARE_KEY, &share_bps);
+}
+
+pub fn is_referrer_registered(env: &Env, referrer: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&(referrer_key(referrer), referrer.clone()))
+}
+
+/// Register a referrer, optionally overriding the default share
+pub fn register_referrer(env: &Env, referrer: &Address, share_bps: Option<u32>) {
+    let key = &(referrer_key(referrer), referrer.clone());
+    env.storage().persistent().set(key, &true);
+    extend_persistent_ttl(env, key);
+
+    let share_key = &(referrer_share_key(referrer), referrer.clone());
+    match share_bps {
+        Some(share_bps) => {
+            env.storage().persistent().set(share_key, &share_bps);
+            extend_persistent_ttl(env, share_key);
+        }
+        None => env.storage().persistent().remove(share_key),
+    }
+}
+
+pub fn remove_referrer(env: &Env, referrer: &Address) {
+    env.storage()
+        .persistent()
+        .remove(&(referrer_key(referrer), referrer.clone()));
+    env.storage()
+        .persistent()
+        .remove(&(referrer_share_key(referrer), referrer.clone()));
+}
+
+/// Share of the platform fee a registered referrer receives (bps of the fee)
+pub fn get_referrer_share(env: &Env, referrer: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&(referrer_share_key(referrer), referrer.clone()))
+        .unwrap_or_else(|| get_referral_share(env))
+}
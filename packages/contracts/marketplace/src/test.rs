@@ -1,7 +1,8 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, symbol_short, xdr::ToXdr, Address, Bytes, BytesN, Env, FromVal, IntoVal, Vec};
+use ed25519_dalek::{Signer, SigningKey};
 
 fn create_marketplace_contract<'a>(env: &Env) -> MarketplaceContractClient<'a> {
     MarketplaceContractClient::new(env, &env.register_contract(None, MarketplaceContract {}))
@@ -52,6 +53,7 @@ fn test_create_listing() {
         &seller,
         &100_0000000, // 100 XLM
         &None,
+        &None,
     );
 
     assert_eq!(listing_id, Ok(0));
@@ -77,6 +79,7 @@ fn test_cancel_listing() {
         &seller,
         &100_0000000,
         &None,
+        &None,
     ).unwrap();
 
     contract.cancel_listing(&listing_id, &seller);
@@ -126,6 +129,7 @@ fn test_create_listing_invalid_price() {
         &seller,
         &0, // Invalid: zero price
         &None,
+        &None,
     );
 }
 
@@ -171,9 +175,9 @@ fn test_create_multiple_listings() {
     let nft_contract = Address::generate(&env);
 
     // Create 3 listings
-    let listing_id_1 = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None).unwrap();
-    let listing_id_2 = contract.create_listing(&nft_contract, &2, &seller, &200_0000000, &None).unwrap();
-    let listing_id_3 = contract.create_listing(&nft_contract, &3, &seller, &300_0000000, &None).unwrap();
+    let listing_id_1 = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None, &None).unwrap();
+    let listing_id_2 = contract.create_listing(&nft_contract, &2, &seller, &200_0000000, &None, &None).unwrap();
+    let listing_id_3 = contract.create_listing(&nft_contract, &3, &seller, &300_0000000, &None, &None).unwrap();
 
     assert_eq!(listing_id_1, 0);
     assert_eq!(listing_id_2, 1);
@@ -211,6 +215,7 @@ fn test_listing_with_expiry() {
         &seller,
         &100_0000000,
         &Some(expiry),
+        &None,
     ).unwrap();
 
     let listing = contract.get_listing(&listing_id).unwrap();
@@ -239,6 +244,7 @@ fn test_create_listing_expired_time_fails() {
         &seller,
         &100_0000000,
         &Some(500), // Past timestamp
+        &None,
     );
 }
 
@@ -259,6 +265,7 @@ fn test_cancel_listing_unauthorized() {
         &seller,
         &100_0000000,
         &None,
+        &None,
     ).unwrap();
 
     // Other user tries to cancel seller's listing - should fail
@@ -281,6 +288,7 @@ fn test_cancel_already_cancelled_listing() {
         &seller,
         &100_0000000,
         &None,
+        &None,
     ).unwrap();
 
     contract.cancel_listing(&listing_id, &seller);
@@ -372,10 +380,14 @@ fn test_update_platform_fee() {
 
     assert_eq!(contract.get_platform_fee(), 250); // Initial 2.5%
 
-    // Update to 5%
-    contract.set_platform_fee(&admin, &500);
+    // Lower to 1%
+    contract.set_platform_fee(&admin, &100);
+
+    assert_eq!(contract.get_platform_fee(), 100);
 
-    assert_eq!(contract.get_platform_fee(), 500);
+    // Increases must be scheduled
+    let result = contract.try_set_platform_fee(&admin, &200);
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
 }
 
 #[test]
@@ -413,7 +425,10 @@ fn test_update_fee_recipient() {
 
     contract.set_fee_recipient(&admin, &new_recipient);
 
-    // Successfully updated (no error means success)
+    let (emitter, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(emitter, contract.address);
+    assert_eq!(topics, (symbol_short!("fee_rcpt"),).into_val(&env));
+    assert_eq!(Address::from_val(&env, &data), new_recipient);
 }
 
 #[test]
@@ -508,3 +523,2916 @@ fn test_make_offer_expired_time_fails() {
         &Some(500),
     );
 }
+
+// ========== PRIVATE LISTING TESTS ==========
+
+#[test]
+fn test_create_reserved_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let reserved_buyer = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(
+        &nft_contract,
+        &1,
+        &seller,
+        &100_0000000,
+        &None,
+        &Some(reserved_buyer.clone()),
+    );
+
+    let listing = contract.get_listing(&listing_id);
+    assert_eq!(listing.reserved_buyer, Some(reserved_buyer.clone()));
+
+    let (reserved, total) = contract.get_reserved_listings(&reserved_buyer, &0, &10);
+    assert_eq!(total, 1);
+    assert_eq!(reserved.get(0), Some(listing_id));
+}
+
+#[test]
+fn test_buy_reserved_listing_wrong_buyer_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let reserved_buyer = Address::generate(&env);
+    let other_buyer = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(
+        &nft_contract,
+        &1,
+        &seller,
+        &100_0000000,
+        &None,
+        &Some(reserved_buyer),
+    );
+
+    // Rejected before any payment is attempted
+    let result = contract.try_buy(&listing_id, &other_buyer, &None);
+    assert_eq!(result, Err(Ok(Error::NotReservedBuyer)));
+}
+
+#[test]
+fn test_cancel_reserved_listing_clears_index() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let reserved_buyer = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(
+        &nft_contract,
+        &1,
+        &seller,
+        &100_0000000,
+        &None,
+        &Some(reserved_buyer.clone()),
+    );
+
+    contract.cancel_listing(&listing_id, &seller);
+
+    assert_eq!(contract.get_reserved_listings(&reserved_buyer, &0, &10).1, 0);
+}
+
+#[test]
+fn test_reserved_listings_paginated() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let reserved_buyer = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    for token_id in 1..=3 {
+        contract.create_listing(
+            &nft_contract,
+            &token_id,
+            &seller,
+            &100_0000000,
+            &None,
+            &Some(reserved_buyer.clone()),
+        );
+    }
+
+    let (page, total) = contract.get_reserved_listings(&reserved_buyer, &2, &10);
+    assert_eq!(total, 3);
+    assert_eq!(page, Vec::from_array(&env, [2]));
+}
+
+#[test]
+fn test_reserve_listing_for_seller_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let result = contract.try_create_listing(
+        &nft_contract,
+        &1,
+        &seller,
+        &100_0000000,
+        &None,
+        &Some(seller.clone()),
+    );
+    assert_eq!(result, Err(Ok(Error::CannotBuyOwnListing)));
+}
+
+// ========== SIGNED ORDER TESTS ==========
+
+fn build_order(env: &Env, seller: &Address, nonce: u64) -> Order {
+    Order {
+        seller: seller.clone(),
+        nft_contract: Address::generate(env),
+        token_id: 1,
+        price: 100_0000000,
+        payment_token: Address::generate(env),
+        expires_at: env.ledger().timestamp() + 3600,
+        salt: 42,
+        nonce,
+    }
+}
+
+#[test]
+fn test_set_order_signer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let public_key = BytesN::from_array(&env, &[7; 32]);
+
+    assert_eq!(contract.get_order_signer(&seller), None);
+
+    contract.set_order_signer(&seller, &public_key);
+
+    assert_eq!(contract.get_order_signer(&seller), Some(public_key));
+}
+
+#[test]
+fn test_order_hash_depends_on_salt() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+
+    let order = build_order(&env, &seller, 0);
+    let mut other = order.clone();
+    other.salt = 43;
+
+    assert_eq!(contract.get_order_hash(&order), contract.get_order_hash(&order));
+    assert_ne!(contract.get_order_hash(&order), contract.get_order_hash(&other));
+}
+
+#[test]
+fn test_cancel_orders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let signature = BytesN::from_array(&env, &[0; 64]);
+
+    let order = build_order(&env, &seller, 0);
+    contract.cancel_orders(&seller, &Vec::from_array(&env, [order.clone()]));
+
+    let order_hash = contract.get_order_hash(&order);
+    assert_eq!(contract.get_order_state(&order_hash), Some(OrderState::Cancelled));
+
+    let result = contract.try_fulfill_order(&order, &signature, &buyer);
+    assert_eq!(result, Err(Ok(Error::OrderCancelled)));
+}
+
+#[test]
+fn test_cancel_orders_of_other_seller_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let other_user = Address::generate(&env);
+
+    let order = build_order(&env, &seller, 0);
+    let result = contract.try_cancel_orders(&other_user, &Vec::from_array(&env, [order]));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_increment_seller_nonce_voids_orders() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let signature = BytesN::from_array(&env, &[0; 64]);
+
+    let order = build_order(&env, &seller, 0);
+
+    assert_eq!(contract.increment_seller_nonce(&seller), 1);
+    assert_eq!(contract.get_seller_nonce(&seller), 1);
+
+    let result = contract.try_fulfill_order(&order, &signature, &buyer);
+    assert_eq!(result, Err(Ok(Error::InvalidOrderNonce)));
+}
+
+#[test]
+fn test_fulfill_order_without_signer_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let signature = BytesN::from_array(&env, &[0; 64]);
+
+    let order = build_order(&env, &seller, 0);
+
+    let result = contract.try_fulfill_order(&order, &signature, &buyer);
+    assert_eq!(result, Err(Ok(Error::OrderSignerNotSet)));
+}
+
+#[test]
+fn test_fulfill_expired_order_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let signature = BytesN::from_array(&env, &[0; 64]);
+
+    let order = build_order(&env, &seller, 0);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = order.expires_at + 1;
+    });
+
+    let result = contract.try_fulfill_order(&order, &signature, &buyer);
+    assert_eq!(result, Err(Ok(Error::OrderExpired)));
+}
+
+fn sign_order(env: &Env, contract: &MarketplaceContractClient, key: &SigningKey, order: &Order) -> BytesN<64> {
+    let order_hash = contract.get_order_hash(order);
+    BytesN::from_array(env, &key.sign(&order_hash.to_array()).to_bytes())
+}
+
+#[test]
+fn test_fulfill_signed_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let key = SigningKey::from_bytes(&[1; 32]);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    contract.set_order_signer(&seller, &BytesN::from_array(&env, &key.verifying_key().to_bytes()));
+
+    let mut order = build_order(&env, &seller, 0);
+    order.nft_contract = nft.address.clone();
+    order.payment_token = xlm.address.clone();
+    let signature = sign_order(&env, &contract, &key, &order);
+
+    contract.fulfill_order(&order, &signature, &buyer);
+
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 900_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 97_5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_5000000);
+
+    let order_hash = contract.get_order_hash(&order);
+    assert_eq!(contract.get_order_state(&order_hash), Some(OrderState::Filled));
+
+    // The same signed order cannot be replayed
+    let result = contract.try_fulfill_order(&order, &signature, &buyer);
+    assert_eq!(result, Err(Ok(Error::OrderAlreadyFilled)));
+}
+
+#[test]
+fn test_fulfill_order_with_tampered_signature_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let key = SigningKey::from_bytes(&[1; 32]);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    contract.set_order_signer(&seller, &BytesN::from_array(&env, &key.verifying_key().to_bytes()));
+
+    let mut order = build_order(&env, &seller, 0);
+    order.nft_contract = nft.address.clone();
+    order.payment_token = xlm.address.clone();
+    let signature = sign_order(&env, &contract, &key, &order);
+
+    // Signed at 100 XLM, submitted at 1 XLM
+    let mut tampered = order.clone();
+    tampered.price = 1_0000000;
+
+    // Passes every other check, then fails ed25519 verification (a host error)
+    let result = contract.try_fulfill_order(&tampered, &signature, &buyer);
+    assert!(matches!(result, Err(Err(_))));
+
+    assert_eq!(nft.owner_of(&1), seller);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 1000_0000000);
+    assert_eq!(contract.get_order_state(&contract.get_order_hash(&tampered)), None);
+}
+
+// ========== SETTLEMENT HELPERS ==========
+
+/// Minimal NFT contract exposing the interface the marketplace calls
+mod mock_nft {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Bytes, Env, Vec};
+
+    #[contract]
+    pub struct MockNFT;
+
+    #[contractimpl]
+    impl MockNFT {
+        pub fn mock_mint(env: Env, to: Address, token_id: u64) {
+            env.storage().persistent().set(&(symbol_short!("owner"), token_id), &to);
+        }
+
+        pub fn mock_set_balance(env: Env, owner: Address, balance: u64) {
+            env.storage().persistent().set(&(symbol_short!("balance"), owner), &balance);
+        }
+
+        pub fn balance_of(env: Env, owner: Address) -> u64 {
+            env.storage()
+                .persistent()
+                .get(&(symbol_short!("balance"), owner))
+                .unwrap_or(0)
+        }
+
+        pub fn mock_set_royalty(env: Env, receiver: Address, royalty_bps: u32) {
+            Self::mock_set_royalty_splits(env.clone(), Vec::from_array(&env, [(receiver, royalty_bps)]));
+        }
+
+        pub fn mock_set_royalty_splits(env: Env, splits: Vec<(Address, u32)>) {
+            env.storage().instance().set(&symbol_short!("royalty"), &splits);
+        }
+
+        pub fn mock_approve_all(env: Env, owner: Address, operator: Address) {
+            env.storage().persistent().set(&(symbol_short!("op_appr"), owner, operator), &true);
+        }
+
+        pub fn get_approved(env: Env, token_id: u64) -> Option<Address> {
+            let _ = (env, token_id);
+            None
+        }
+
+        pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+            env.storage()
+                .persistent()
+                .get(&(symbol_short!("op_appr"), owner, operator))
+                .unwrap_or(false)
+        }
+
+        pub fn owner_of(env: Env, token_id: u64) -> Address {
+            env.storage().persistent().get(&(symbol_short!("owner"), token_id)).unwrap()
+        }
+
+        pub fn transfer(env: Env, from: Address, to: Address, token_id: u64) {
+            from.require_auth();
+            assert_eq!(Self::owner_of(env.clone(), token_id), from);
+            env.storage().persistent().set(&(symbol_short!("owner"), token_id), &to);
+        }
+
+        pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u64) {
+            spender.require_auth();
+            assert_eq!(Self::owner_of(env.clone(), token_id), from);
+            env.storage().persistent().set(&(symbol_short!("owner"), token_id), &to);
+        }
+
+        pub fn safe_transfer(env: Env, from: Address, to: Address, token_id: u64, data: Bytes) {
+            Self::transfer(env.clone(), from.clone(), to.clone(), token_id);
+            let ack = crate::MarketplaceContractClient::new(&env, &to).on_nft_received(&from, &from, &token_id, &data);
+            assert_eq!(ack, crate::NFT_RECEIVED);
+        }
+
+        pub fn royalty_info(env: Env, token_id: u64, sale_price: i128) -> (Address, i128) {
+            let splits = Self::royalty_splits(env.clone(), token_id, sale_price);
+            match splits.first() {
+                Some(primary) => primary,
+                None => (env.current_contract_address(), 0),
+            }
+        }
+
+        pub fn royalty_splits(env: Env, token_id: u64, sale_price: i128) -> Vec<(Address, i128)> {
+            let _ = token_id;
+            let splits: Vec<(Address, u32)> = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("royalty"))
+                .unwrap_or(Vec::new(&env));
+
+            let mut amounts = Vec::new(&env);
+            for (receiver, bps) in splits.iter() {
+                amounts.push_back((receiver, (sale_price * bps as i128) / 10000));
+            }
+            amounts
+        }
+    }
+}
+
+use mock_nft::MockNFTClient;
+
+// Initialize marketplace with a real Stellar Asset Contract as payment token
+fn setup_marketplace_with_token(
+    env: &Env,
+) -> (MarketplaceContractClient, Address, Address, soroban_sdk::token::StellarAssetClient) {
+    let contract = create_marketplace_contract(env);
+    let admin = Address::generate(env);
+    let fee_recipient = Address::generate(env);
+    let token_admin = Address::generate(env);
+    let xlm_token = env.register_stellar_asset_contract_v2(token_admin).address();
+
+    contract.initialize(&admin, &250, &fee_recipient, &xlm_token);
+
+    let token_admin_client = soroban_sdk::token::StellarAssetClient::new(env, &xlm_token);
+
+    (contract, admin, fee_recipient, token_admin_client)
+}
+
+fn create_mock_nft<'a>(env: &Env) -> MockNFTClient<'a> {
+    MockNFTClient::new(env, &env.register_contract(None, mock_nft::MockNFT {}))
+}
+
+fn token_balance(env: &Env, token: &Address, owner: &Address) -> i128 {
+    soroban_sdk::token::Client::new(env, token).balance(owner)
+}
+
+// ========== BATCH BUY TESTS ==========
+
+#[test]
+fn test_batch_buy_all() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let listing_1 = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    let listing_2 = contract.create_listing(&nft.address, &2, &seller, &200_0000000, &None, &None);
+
+    let results = contract.batch_buy(
+        &Vec::from_array(&env, [listing_1, listing_2]),
+        &buyer,
+        &300_0000000,
+        &false,
+        &None,
+    );
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results.get(0).unwrap().status, BatchBuyStatus::Purchased);
+    assert_eq!(results.get(1).unwrap().status, BatchBuyStatus::Purchased);
+
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert_eq!(nft.owner_of(&2), buyer);
+
+    // 2.5% platform fee on 300 XLM, held for the fee recipient
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 7_5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 292_5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 700_0000000);
+
+    assert_eq!(contract.get_listing(&listing_1).status, ListingStatus::Sold);
+}
+
+#[test]
+fn test_batch_buy_partial_skips_unavailable() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let listing_1 = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    let listing_2 = contract.create_listing(&nft.address, &2, &seller, &200_0000000, &None, &None);
+    contract.cancel_listing(&listing_2, &seller);
+
+    let results = contract.batch_buy(
+        &Vec::from_array(&env, [listing_1, listing_2, 99]),
+        &buyer,
+        &1000_0000000,
+        &true,
+        &None,
+    );
+
+    assert_eq!(results.get(0).unwrap().status, BatchBuyStatus::Purchased);
+    assert_eq!(results.get(1).unwrap().status, BatchBuyStatus::NotActive);
+    assert_eq!(results.get(2).unwrap().status, BatchBuyStatus::NotFound);
+
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert_eq!(nft.owner_of(&2), seller);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 900_0000000);
+}
+
+#[test]
+fn test_batch_buy_partial_skips_moved_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let listing_1 = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    let listing_2 = contract.create_listing(&nft.address, &2, &seller, &200_0000000, &None, &None);
+
+    // Seller moves token 2 away while it is still listed
+    nft.transfer(&seller, &other, &2);
+
+    let results = contract.batch_buy(
+        &Vec::from_array(&env, [listing_1, listing_2]),
+        &buyer,
+        &1000_0000000,
+        &true,
+        &None,
+    );
+
+    assert_eq!(results.get(0).unwrap().status, BatchBuyStatus::Purchased);
+    assert_eq!(results.get(1).unwrap().status, BatchBuyStatus::Unavailable);
+
+    // Only the first listing is paid for
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert_eq!(nft.owner_of(&2), other);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 900_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 97_5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_5000000);
+    assert_eq!(contract.get_listing(&listing_2).status, ListingStatus::Active);
+}
+
+#[test]
+fn test_batch_buy_partial_skips_blocked_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft_a = create_mock_nft(&env);
+    let nft_b = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft_a.mock_mint(&seller, &1);
+    nft_b.mock_mint(&seller, &1);
+    nft_a.mock_approve_all(&seller, &contract.address);
+    nft_b.mock_approve_all(&seller, &contract.address);
+
+    let listing_1 = contract.create_listing(&nft_b.address, &1, &seller, &200_0000000, &None, &None);
+    let listing_2 = contract.create_listing(&nft_a.address, &1, &seller, &100_0000000, &None, &None);
+
+    contract.set_collection_status(&admin, &nft_b.address, &Some(CollectionStatus::Blocked));
+
+    let results = contract.batch_buy(
+        &Vec::from_array(&env, [listing_1, listing_2]),
+        &buyer,
+        &1000_0000000,
+        &true,
+        &None,
+    );
+
+    assert_eq!(results.get(0).unwrap().status, BatchBuyStatus::Blocked);
+    assert_eq!(results.get(1).unwrap().status, BatchBuyStatus::Purchased);
+
+    // The buyer pays for the one NFT they received, nothing for the blocked one
+    assert_eq!(nft_a.owner_of(&1), buyer);
+    assert_eq!(nft_b.owner_of(&1), seller);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 900_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 97_5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_5000000);
+    assert_eq!(contract.get_seller_sales(&seller), 1);
+}
+
+#[test]
+fn test_batch_buy_over_budget_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let listing_1 = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    let listing_2 = contract.create_listing(&nft.address, &2, &seller, &200_0000000, &None, &None);
+
+    let result = contract.try_batch_buy(
+        &Vec::from_array(&env, [listing_1, listing_2]),
+        &buyer,
+        &150_0000000,
+        &false,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::MaxTotalExceeded)));
+
+    // Nothing moved
+    assert_eq!(nft.owner_of(&1), seller);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 1000_0000000);
+}
+
+#[test]
+fn test_batch_buy_duplicate_listing_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+
+    let result = contract.try_batch_buy(
+        &Vec::from_array(&env, [listing_id, listing_id]),
+        &buyer,
+        &1000_0000000,
+        &false,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::ListingNotActive)));
+}
+
+#[test]
+fn test_batch_buy_empty_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let buyer = Address::generate(&env);
+
+    let result = contract.try_batch_buy(&Vec::new(&env), &buyer, &100, &false, &None);
+    assert_eq!(result, Err(Ok(Error::BatchEmpty)));
+}
+
+// ========== BUNDLE LISTING TESTS ==========
+
+#[test]
+fn test_bundle_listing_buy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft_a = create_mock_nft(&env);
+    let nft_b = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft_a.mock_mint(&seller, &1);
+    nft_b.mock_mint(&seller, &7);
+    nft_a.mock_approve_all(&seller, &contract.address);
+    nft_b.mock_approve_all(&seller, &contract.address);
+
+    // 10% royalty on collection A only
+    nft_a.mock_set_royalty(&artist, &1000);
+
+    let items = Vec::from_array(
+        &env,
+        [(nft_a.address.clone(), 1u64), (nft_b.address.clone(), 7u64)],
+    );
+    let listing_id = contract.create_bundle_listing(&seller, &items, &200_0000000, &None, &None);
+
+    let listing = contract.get_listing(&listing_id);
+    assert_eq!(listing.bundle_items, items);
+
+    contract.buy(&listing_id, &buyer, &None);
+
+    assert_eq!(nft_a.owner_of(&1), buyer);
+    assert_eq!(nft_b.owner_of(&7), buyer);
+
+    // Royalty on item A's 100 XLM share, 2.5% platform fee on the whole price
+    assert_eq!(token_balance(&env, &xlm.address, &artist), 10_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 5_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 185_0000000);
+}
+
+#[test]
+fn test_bundle_listing_requires_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+
+    let items = Vec::from_array(&env, [(nft.address.clone(), 1u64), (nft.address.clone(), 2u64)]);
+    let result = contract.try_create_bundle_listing(&seller, &items, &100_0000000, &None, &None);
+    assert_eq!(result, Err(Ok(Error::MarketplaceNotApproved)));
+}
+
+#[test]
+fn test_bundle_listing_not_owner_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let other_user = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&other_user, &2);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let items = Vec::from_array(&env, [(nft.address.clone(), 1u64), (nft.address.clone(), 2u64)]);
+    let result = contract.try_create_bundle_listing(&seller, &items, &100_0000000, &None, &None);
+    assert_eq!(result, Err(Ok(Error::NotTokenOwner)));
+}
+
+#[test]
+fn test_bundle_listing_duplicate_item_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let items = Vec::from_array(&env, [(nft.address.clone(), 1u64), (nft.address.clone(), 1u64)]);
+    let result = contract.try_create_bundle_listing(&seller, &items, &100_0000000, &None, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidBundle)));
+}
+
+// ========== SWAP TESTS ==========
+
+#[test]
+fn test_swap_with_top_up() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let maker = Address::generate(&env);
+    let taker = Address::generate(&env);
+
+    xlm.mint(&maker, &100_0000000);
+    nft.mock_mint(&maker, &1);
+    nft.mock_mint(&taker, &2);
+    nft.mock_approve_all(&maker, &contract.address);
+
+    let swap_id = contract.propose_swap(
+        &maker,
+        &Vec::from_array(&env, [(nft.address.clone(), 1u64)]),
+        &50_0000000,
+        &taker,
+        &Vec::from_array(&env, [(nft.address.clone(), 2u64)]),
+        &None,
+    );
+
+    // Payment escrowed by the marketplace
+    assert_eq!(token_balance(&env, &xlm.address, &maker), 50_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &contract.address), 50_0000000);
+
+    contract.accept_swap(&swap_id, &taker);
+
+    assert_eq!(nft.owner_of(&1), taker);
+    assert_eq!(nft.owner_of(&2), maker);
+    assert_eq!(token_balance(&env, &xlm.address, &taker), 50_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &contract.address), 0);
+    assert_eq!(contract.get_swap(&swap_id).status, SwapStatus::Accepted);
+}
+
+#[test]
+fn test_cancel_swap_refunds_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let maker = Address::generate(&env);
+    let taker = Address::generate(&env);
+
+    xlm.mint(&maker, &100_0000000);
+    nft.mock_mint(&taker, &2);
+
+    // Payment-only side: buy the taker's NFT through a swap
+    let swap_id = contract.propose_swap(
+        &maker,
+        &Vec::new(&env),
+        &80_0000000,
+        &taker,
+        &Vec::from_array(&env, [(nft.address.clone(), 2u64)]),
+        &None,
+    );
+
+    contract.cancel_swap(&swap_id, &maker);
+
+    assert_eq!(token_balance(&env, &xlm.address, &maker), 100_0000000);
+    assert_eq!(contract.get_swap(&swap_id).status, SwapStatus::Cancelled);
+
+    let result = contract.try_accept_swap(&swap_id, &taker);
+    assert_eq!(result, Err(Ok(Error::SwapNotOpen)));
+}
+
+#[test]
+fn test_accept_expired_swap_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let maker = Address::generate(&env);
+    let taker = Address::generate(&env);
+
+    nft.mock_mint(&maker, &1);
+    nft.mock_mint(&taker, &2);
+    nft.mock_approve_all(&maker, &contract.address);
+
+    let swap_id = contract.propose_swap(
+        &maker,
+        &Vec::from_array(&env, [(nft.address.clone(), 1u64)]),
+        &0,
+        &taker,
+        &Vec::from_array(&env, [(nft.address.clone(), 2u64)]),
+        &Some(1000),
+    );
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1001;
+    });
+
+    let result = contract.try_accept_swap(&swap_id, &taker);
+    assert_eq!(result, Err(Ok(Error::SwapExpired)));
+}
+
+#[test]
+fn test_accept_swap_wrong_taker_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let maker = Address::generate(&env);
+    let taker = Address::generate(&env);
+    let other_user = Address::generate(&env);
+
+    nft.mock_mint(&maker, &1);
+    nft.mock_mint(&taker, &2);
+    nft.mock_approve_all(&maker, &contract.address);
+
+    let swap_id = contract.propose_swap(
+        &maker,
+        &Vec::from_array(&env, [(nft.address.clone(), 1u64)]),
+        &0,
+        &taker,
+        &Vec::from_array(&env, [(nft.address.clone(), 2u64)]),
+        &None,
+    );
+
+    let result = contract.try_accept_swap(&swap_id, &other_user);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_propose_swap_taker_not_owner_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let maker = Address::generate(&env);
+    let taker = Address::generate(&env);
+
+    nft.mock_mint(&maker, &1);
+    nft.mock_mint(&maker, &2);
+    nft.mock_approve_all(&maker, &contract.address);
+
+    let result = contract.try_propose_swap(
+        &maker,
+        &Vec::from_array(&env, [(nft.address.clone(), 1u64)]),
+        &0,
+        &taker,
+        &Vec::from_array(&env, [(nft.address.clone(), 2u64)]),
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::NotTokenOwner)));
+}
+
+#[test]
+fn test_propose_swap_duplicate_items_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let maker = Address::generate(&env);
+    let taker = Address::generate(&env);
+
+    nft.mock_mint(&maker, &1);
+    nft.mock_mint(&taker, &2);
+    nft.mock_approve_all(&maker, &contract.address);
+
+    let result = contract.try_propose_swap(
+        &maker,
+        &Vec::from_array(&env, [(nft.address.clone(), 1u64)]),
+        &0,
+        &taker,
+        &Vec::from_array(&env, [(nft.address.clone(), 2u64), (nft.address.clone(), 2u64)]),
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidSwap)));
+}
+
+// ========== UPDATE LISTING TESTS ==========
+
+#[test]
+fn test_update_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None, &None);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2000;
+    });
+
+    contract.update_listing(&listing_id, &seller, &80_0000000, &Some(5000));
+
+    let listing = contract.get_listing(&listing_id);
+    assert_eq!(listing.price, 80_0000000);
+    assert_eq!(listing.expires_at, Some(5000));
+    assert_eq!(listing.status, ListingStatus::Active);
+
+    let history = contract.get_price_history(&listing_id);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.get(0).unwrap(), PricePoint { price: 100_0000000, timestamp: 1000 });
+    assert_eq!(history.get(1).unwrap(), PricePoint { price: 80_0000000, timestamp: 2000 });
+}
+
+#[test]
+fn test_update_listing_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None, &None);
+
+    let result = contract.try_update_listing(&listing_id, &other_user, &80_0000000, &None);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_update_listing_invalid_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None, &None);
+
+    let result = contract.try_update_listing(&listing_id, &seller, &0, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidPrice)));
+}
+
+#[test]
+fn test_update_cancelled_listing_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None, &None);
+    contract.cancel_listing(&listing_id, &seller);
+
+    let result = contract.try_update_listing(&listing_id, &seller, &80_0000000, &None);
+    assert_eq!(result, Err(Ok(Error::ListingNotActive)));
+}
+
+// ========== ACTIVE LISTING INDEX TESTS ==========
+
+#[test]
+fn test_active_listings_pagination_across_buckets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    for token_id in 0..150u64 {
+        contract.create_listing(&nft_contract, &token_id, &seller, &100_0000000, &None, &None);
+    }
+
+    let (page, total) = contract.active_listings(&0, &100);
+    assert_eq!(total, 150);
+    assert_eq!(page.len(), 100);
+
+    let (page, _) = contract.active_listings(&140, &100);
+    assert_eq!(page.len(), 10);
+    assert_eq!(page.get(9), Some(149));
+
+    // Removing from the first bucket swaps in the last ID from the second
+    contract.cancel_listing(&0, &seller);
+
+    let (page, total) = contract.active_listings(&0, &1);
+    assert_eq!(total, 149);
+    assert_eq!(page.get(0), Some(149));
+
+    let (page, _) = contract.active_listings(&148, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0), Some(148));
+}
+
+#[test]
+fn test_active_listings_by_collection_and_seller() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller_1 = Address::generate(&env);
+    let seller_2 = Address::generate(&env);
+    let collection_a = Address::generate(&env);
+    let collection_b = Address::generate(&env);
+
+    let listing_1 = contract.create_listing(&collection_a, &1, &seller_1, &100_0000000, &None, &None);
+    let listing_2 = contract.create_listing(&collection_b, &1, &seller_1, &100_0000000, &None, &None);
+    let listing_3 = contract.create_listing(&collection_a, &2, &seller_2, &100_0000000, &None, &None);
+
+    let (by_collection, total) = contract.active_listings_by_collection(&collection_a, &0, &10);
+    assert_eq!(total, 2);
+    assert_eq!(by_collection, Vec::from_array(&env, [listing_1, listing_3]));
+
+    let (by_seller, total) = contract.active_listings_by_seller(&seller_1, &0, &10);
+    assert_eq!(total, 2);
+    assert_eq!(by_seller, Vec::from_array(&env, [listing_1, listing_2]));
+
+    contract.cancel_listing(&listing_1, &seller_1);
+
+    let (by_collection, _) = contract.active_listings_by_collection(&collection_a, &0, &10);
+    assert_eq!(by_collection, Vec::from_array(&env, [listing_3]));
+
+    let (by_seller, _) = contract.active_listings_by_seller(&seller_1, &0, &10);
+    assert_eq!(by_seller, Vec::from_array(&env, [listing_2]));
+
+    let (all, total) = contract.active_listings(&0, &10);
+    assert_eq!(total, 2);
+    assert_eq!(all, Vec::from_array(&env, [listing_3, listing_2]));
+}
+
+// ========== PER-TOKEN LOOKUP TESTS ==========
+
+#[test]
+fn test_listing_for_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    assert_eq!(contract.listing_for_token(&nft_contract, &1), None);
+
+    let listing_id = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None, &None);
+    assert_eq!(contract.listing_for_token(&nft_contract, &1), Some(listing_id));
+
+    contract.cancel_listing(&listing_id, &seller);
+    assert_eq!(contract.listing_for_token(&nft_contract, &1), None);
+}
+
+#[test]
+fn test_listing_for_token_cleared_after_buy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.buy(&listing_id, &buyer, &None);
+
+    assert_eq!(contract.listing_for_token(&nft.address, &1), None);
+    assert_eq!(contract.active_listings(&0, &10).1, 0);
+}
+
+#[test]
+fn test_offers_for_token_and_buyer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let buyer_1 = Address::generate(&env);
+    let buyer_2 = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let offer_1 = contract.make_offer(&nft_contract, &1, &buyer_1, &50_0000000, &None);
+    let offer_2 = contract.make_offer(&nft_contract, &1, &buyer_2, &60_0000000, &None);
+    let offer_3 = contract.make_offer(&nft_contract, &2, &buyer_1, &70_0000000, &None);
+
+    let (for_token, total) = contract.offers_for_token(&nft_contract, &1, &0, &10);
+    assert_eq!(total, 2);
+    assert_eq!(for_token, Vec::from_array(&env, [offer_1, offer_2]));
+
+    let (by_buyer, total) = contract.offers_by_buyer(&buyer_1, &0, &10);
+    assert_eq!(total, 2);
+    assert_eq!(by_buyer, Vec::from_array(&env, [offer_1, offer_3]));
+
+    contract.cancel_offer(&offer_1, &buyer_1);
+
+    let (for_token, _) = contract.offers_for_token(&nft_contract, &1, &0, &10);
+    assert_eq!(for_token, Vec::from_array(&env, [offer_2]));
+
+    let (by_buyer, _) = contract.offers_by_buyer(&buyer_1, &0, &10);
+    assert_eq!(by_buyer, Vec::from_array(&env, [offer_3]));
+}
+
+#[test]
+fn test_offers_for_token_cleared_after_accept() {
+    let env = Env::default();
+    // Buyer's payment auth is a non-root authorization within accept_offer
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+
+    let offer_id = contract.make_offer(&nft.address, &1, &buyer, &50_0000000, &None);
+    contract.accept_offer(&offer_id, &seller, &None);
+
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert_eq!(contract.offers_for_token(&nft.address, &1, &0, &10).1, 0);
+    assert_eq!(contract.offers_by_buyer(&buyer, &0, &10).1, 0);
+}
+
+// ========== LISTING INVALIDATION TESTS ==========
+
+#[test]
+fn test_on_token_moved_invalidates_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None, &None);
+
+    contract.on_token_moved(&nft_contract, &1, &seller, &Some(new_owner));
+
+    assert_eq!(contract.get_listing(&listing_id).status, ListingStatus::Invalidated);
+    assert_eq!(contract.listing_for_token(&nft_contract, &1), None);
+    assert_eq!(contract.active_listings(&0, &10).1, 0);
+}
+
+#[test]
+fn test_on_token_moved_ignores_other_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let seller = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let listing_id = contract.create_listing(&nft_contract, &1, &seller, &100_0000000, &None, &None);
+
+    contract.on_token_moved(&nft_contract, &2, &seller, &None);
+
+    assert_eq!(contract.get_listing(&listing_id).status, ListingStatus::Active);
+}
+
+#[test]
+fn test_prune_invalid_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+
+    let result = contract.try_prune_invalid_listing(&listing_id);
+    assert_eq!(result, Err(Ok(Error::ListingStillValid)));
+
+    // Token moved without notifying the marketplace
+    nft.mock_mint(&new_owner, &1);
+
+    contract.prune_invalid_listing(&listing_id);
+    assert_eq!(contract.get_listing(&listing_id).status, ListingStatus::Invalidated);
+}
+
+#[test]
+fn test_token_listed_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let new_owner = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let first = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    let result = contract.try_create_listing(&nft.address, &1, &seller, &90_0000000, &None, &None);
+    assert_eq!(result, Err(Ok(Error::ListingStillValid)));
+
+    // The token moved without notifying the marketplace: the stale listing
+    // makes way for the new owner's
+    nft.mock_mint(&new_owner, &1);
+    nft.mock_approve_all(&new_owner, &contract.address);
+    let second = contract.create_listing(&nft.address, &1, &new_owner, &120_0000000, &None, &None);
+
+    assert_eq!(contract.get_listing(&first).status, ListingStatus::Invalidated);
+    assert_eq!(contract.listing_for_token(&nft.address, &1), Some(second));
+
+    contract.cancel_listing(&second, &new_owner);
+    assert_eq!(contract.listing_for_token(&nft.address, &1), None);
+}
+
+#[test]
+fn test_accepted_offer_invalidates_listing() {
+    let env = Env::default();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    let offer_id = contract.make_offer(&nft.address, &1, &buyer, &50_0000000, &None);
+    contract.accept_offer(&offer_id, &seller, &None);
+
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert_eq!(contract.get_listing(&listing_id).status, ListingStatus::Invalidated);
+    assert_eq!(contract.listing_for_token(&nft.address, &1), None);
+    assert_eq!(contract.active_listings(&0, &10).1, 0);
+    assert_eq!(contract.active_listings_by_seller(&seller, &0, &10).1, 0);
+}
+
+#[test]
+fn test_raffle_deposit_via_safe_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+
+    let deposit = RaffleDeposit {
+        nft_contract: nft.address.clone(),
+        terms: raffle_terms(20, 10),
+    };
+    nft.safe_transfer(&seller, &contract.address, &1, &deposit.to_xdr(&env));
+
+    assert_eq!(nft.owner_of(&1), contract.address);
+    let raffle = contract.get_raffle(&0);
+    assert_eq!(raffle.seller, seller);
+    assert_eq!(raffle.nft_contract, nft.address);
+    assert_eq!(raffle.terms, raffle_terms(20, 10));
+}
+
+#[test]
+fn test_marketplace_rejects_other_safe_transfers() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let operator = Address::generate(&env);
+
+    let deposit = RaffleDeposit {
+        nft_contract: nft.address.clone(),
+        terms: raffle_terms(20, 10),
+    }
+    .to_xdr(&env);
+
+    // No raffle deposit in `data`
+    let result = contract.try_on_nft_received(&seller, &seller, &1, &seller.clone().to_xdr(&env));
+    assert_eq!(result, Err(Ok(Error::InvalidRaffle)));
+
+    // Only the owner may deposit their token
+    let result = contract.try_on_nft_received(&operator, &seller, &1, &deposit);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Callers other than the named NFT contract fail its auth check
+    env.set_auths(&[]);
+    let result = contract.try_on_nft_received(&seller, &seller, &1, &deposit);
+    assert!(matches!(result, Err(Err(_))));
+}
+
+// ========== EXPIRY CLEANUP TESTS ==========
+
+#[test]
+fn test_expire_listings() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+
+    let expiring = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &Some(2000), &None);
+    let open_ended = contract.create_listing(&nft.address, &2, &seller, &100_0000000, &None, &None);
+
+    // Nothing has expired yet
+    let removed = contract.expire_listings(&Vec::from_array(&env, [expiring, open_ended]));
+    assert_eq!(removed.len(), 0);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2001;
+    });
+
+    let (expired, total) = contract.expired_listings(&0, &10);
+    assert_eq!(expired, Vec::from_array(&env, [expiring]));
+    assert_eq!(total, 2);
+
+    // Unknown IDs are skipped
+    let removed = contract.expire_listings(&Vec::from_array(&env, [expiring, open_ended, 99]));
+    assert_eq!(removed, Vec::from_array(&env, [expiring]));
+
+    assert!(contract.try_get_listing(&expiring).is_err());
+    assert_eq!(contract.get_price_history(&expiring).len(), 0);
+    assert_eq!(contract.listing_for_token(&nft.address, &1), None);
+    assert_eq!(contract.active_listings(&0, &10), (Vec::from_array(&env, [open_ended]), 1));
+    assert_eq!(contract.expired_listings(&0, &10).0.len(), 0);
+}
+
+#[test]
+fn test_expired_listing_stays_listed_after_failed_buy() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &Some(2000), &None);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2001;
+    });
+
+    assert_eq!(contract.try_buy(&listing_id, &buyer, &None), Err(Ok(Error::ListingExpired)));
+    assert_eq!(contract.expired_listings(&0, &10).0, Vec::from_array(&env, [listing_id]));
+
+    // A partial batch skips it without pruning it either
+    let results = contract.batch_buy(&Vec::from_array(&env, [listing_id]), &buyer, &1000_0000000, &true, &None);
+    assert_eq!(results.get(0).unwrap().status, BatchBuyStatus::Expired);
+    assert_eq!(contract.expired_listings(&0, &10).0, Vec::from_array(&env, [listing_id]));
+
+    contract.expire_listings(&Vec::from_array(&env, [listing_id]));
+    assert_eq!(contract.expired_listings(&0, &10), (Vec::new(&env), 0));
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 1000_0000000);
+}
+
+#[test]
+fn test_expire_offers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1000;
+    });
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+    let buyer = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let expiring = contract.make_offer(&nft_contract, &1, &buyer, &50_0000000, &Some(2000));
+    let open_ended = contract.make_offer(&nft_contract, &1, &buyer, &60_0000000, &None);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = 2001;
+    });
+
+    let (expired, total) = contract.expired_offers(&0, &10);
+    assert_eq!(expired, Vec::from_array(&env, [expiring]));
+    assert_eq!(total, 2);
+
+    let removed = contract.expire_offers(&Vec::from_array(&env, [expiring, open_ended]));
+    assert_eq!(removed, Vec::from_array(&env, [expiring]));
+
+    assert!(contract.try_get_offer(&expiring).is_err());
+    assert_eq!(contract.offers_for_token(&nft_contract, &1, &0, &10), (Vec::from_array(&env, [open_ended]), 1));
+    assert_eq!(contract.offers_by_buyer(&buyer, &0, &10), (Vec::from_array(&env, [open_ended]), 1));
+}
+
+#[test]
+fn test_expire_listings_empty_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace(&env);
+
+    let result = contract.try_expire_listings(&Vec::new(&env));
+    assert_eq!(result, Err(Ok(Error::BatchEmpty)));
+}
+
+// ========== PAYOUT LEDGER TESTS ==========
+
+#[test]
+fn test_fees_and_royalties_accrue_until_withdrawn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_set_royalty(&artist, &500);
+
+    contract.set_payout_mode(&admin, &fee_recipient, &PayoutMode::Pull);
+    contract.set_payout_mode(&admin, &artist, &PayoutMode::Pull);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.buy(&listing_id, &buyer, &None);
+
+    // Seller is paid immediately; fee and royalty stay in the marketplace
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 92_5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &contract.address), 7_5000000);
+    assert_eq!(contract.claimable(&artist, &xlm.address), 5_0000000);
+    assert_eq!(contract.claimable(&fee_recipient, &xlm.address), 2_5000000);
+
+    assert_eq!(contract.withdraw(&artist, &xlm.address), 5_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &artist), 5_0000000);
+    assert_eq!(contract.claimable(&artist, &xlm.address), 0);
+
+    let result = contract.try_withdraw(&artist, &xlm.address);
+    assert_eq!(result, Err(Ok(Error::NothingToWithdraw)));
+}
+
+#[test]
+fn test_pull_payout_mode() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_set_royalty(&artist, &500);
+
+    assert_eq!(contract.get_payout_mode(&fee_recipient), PayoutMode::Push);
+    contract.set_payout_mode(&admin, &fee_recipient, &PayoutMode::Pull);
+    assert_eq!(contract.get_payout_mode(&fee_recipient), PayoutMode::Pull);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.buy(&listing_id, &buyer, &None);
+
+    // Fee accrues, royalty still pushed
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 0);
+    assert_eq!(contract.claimable(&fee_recipient, &xlm.address), 2_5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &artist), 5_0000000);
+    assert_eq!(contract.claimable(&artist, &xlm.address), 0);
+}
+
+#[test]
+fn test_set_payout_mode_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, _) = setup_marketplace_with_token(&env);
+    let not_admin = Address::generate(&env);
+
+    let result = contract.try_set_payout_mode(&not_admin, &fee_recipient, &PayoutMode::Push);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+// ========== ROYALTY SPLIT TESTS ==========
+
+#[test]
+fn test_buy_pays_every_royalty_recipient() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let producer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+
+    // 6% to the artist, 2% to the producer
+    nft.mock_set_royalty_splits(&Vec::from_array(
+        &env,
+        [(artist.clone(), 600u32), (producer.clone(), 200u32)],
+    ));
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.buy(&listing_id, &buyer, &None);
+
+    assert_eq!(token_balance(&env, &xlm.address, &artist), 6_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &producer), 2_0000000);
+    // 100 - 2.5 fee - 8 royalties
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 89_5000000);
+}
+
+// ========== UNTRUSTED ROYALTY TESTS ==========
+
+/// NFT contract reporting whatever royalty amounts it is told to
+mod malicious_nft {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Vec};
+
+    #[contract]
+    pub struct MaliciousNFT;
+
+    #[contractimpl]
+    impl MaliciousNFT {
+        pub fn mock_mint(env: Env, to: Address, token_id: u64) {
+            env.storage().persistent().set(&(symbol_short!("owner"), token_id), &to);
+        }
+
+        pub fn mock_set_royalties(env: Env, royalties: Vec<(Address, i128)>) {
+            env.storage().instance().set(&symbol_short!("royalty"), &royalties);
+        }
+
+        pub fn get_approved(env: Env, token_id: u64) -> Option<Address> {
+            let _ = (env, token_id);
+            None
+        }
+
+        pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+            let _ = (env, owner, operator);
+            true
+        }
+
+        pub fn owner_of(env: Env, token_id: u64) -> Address {
+            env.storage().persistent().get(&(symbol_short!("owner"), token_id)).unwrap()
+        }
+
+        pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u64) {
+            spender.require_auth();
+            assert_eq!(Self::owner_of(env.clone(), token_id), from);
+            env.storage().persistent().set(&(symbol_short!("owner"), token_id), &to);
+        }
+
+        pub fn royalty_splits(env: Env, token_id: u64, sale_price: i128) -> Vec<(Address, i128)> {
+            let _ = (token_id, sale_price);
+            env.storage()
+                .instance()
+                .get(&symbol_short!("royalty"))
+                .unwrap_or(Vec::new(&env))
+        }
+    }
+}
+
+fn setup_malicious_listing(
+    env: &Env,
+    royalties: Vec<(Address, i128)>,
+) -> (MarketplaceContractClient, Address, soroban_sdk::token::StellarAssetClient, u64, Address) {
+    let (contract, admin, _, xlm) = setup_marketplace_with_token(env);
+    let nft = malicious_nft::MaliciousNFTClient::new(
+        env,
+        &env.register_contract(None, malicious_nft::MaliciousNFT {}),
+    );
+    let seller = Address::generate(env);
+    let buyer = Address::generate(env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+
+    nft.mock_set_royalties(&royalties);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+
+    (contract, admin, xlm, listing_id, buyer)
+}
+
+#[test]
+fn test_royalty_above_price_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let attacker = Address::generate(&env);
+    let (contract, _, _, listing_id, buyer) =
+        setup_malicious_listing(&env, Vec::from_array(&env, [(attacker, 500_0000000)]));
+
+    let result = contract.try_buy(&listing_id, &buyer, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidRoyalty)));
+}
+
+#[test]
+fn test_negative_royalty_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let attacker = Address::generate(&env);
+    let (contract, _, _, listing_id, buyer) =
+        setup_malicious_listing(&env, Vec::from_array(&env, [(attacker, -50_0000000)]));
+
+    let result = contract.try_buy(&listing_id, &buyer, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidRoyalty)));
+}
+
+#[test]
+fn test_royalty_over_admin_max_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let artist = Address::generate(&env);
+    // 8% is within the 10% default
+    let (contract, admin, xlm, listing_id, buyer) =
+        setup_malicious_listing(&env, Vec::from_array(&env, [(artist.clone(), 8_0000000)]));
+
+    contract.set_max_royalty(&admin, &500);
+    assert_eq!(contract.get_max_royalty(), 500);
+
+    let result = contract.try_buy(&listing_id, &buyer, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidRoyalty)));
+
+    contract.set_max_royalty(&admin, &1000);
+    contract.buy(&listing_id, &buyer, &None);
+    assert_eq!(token_balance(&env, &xlm.address, &artist), 8_0000000);
+}
+
+#[test]
+fn test_self_royalty_receivers_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let artist = Address::generate(&env);
+    let (contract, _, xlm, listing_id, buyer) = setup_malicious_listing(&env, Vec::new(&env));
+    let nft_contract = contract.get_listing(&listing_id).nft_contract;
+    let nft = malicious_nft::MaliciousNFTClient::new(&env, &nft_contract);
+
+    // Receivers pointing at the marketplace, the collection itself, or owing
+    // a negative amount
+    for receiver in [
+        (contract.address.clone(), 5_0000000),
+        (nft_contract.clone(), 5_0000000),
+        (artist.clone(), -1),
+    ] {
+        nft.mock_set_royalties(&Vec::from_array(&env, [receiver]));
+        let result = contract.try_buy(&listing_id, &buyer, &None);
+        assert_eq!(result, Err(Ok(Error::InvalidRoyalty)));
+    }
+
+    assert_eq!(nft.owner_of(&1), contract.get_listing(&listing_id).seller);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 1000_0000000);
+}
+
+#[test]
+fn test_royalty_rounding_to_zero_is_skipped() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let producer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+
+    // 1 bps of 9999 stroops rounds down to nothing
+    nft.mock_set_royalty_splits(&Vec::from_array(
+        &env,
+        [(artist.clone(), 1u32), (producer.clone(), 500u32)],
+    ));
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &9999, &None, &None);
+    contract.buy(&listing_id, &buyer, &None);
+
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert_eq!(token_balance(&env, &xlm.address, &artist), 0);
+    assert_eq!(token_balance(&env, &xlm.address, &producer), 499);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 249);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 9251);
+}
+
+#[test]
+fn test_set_max_royalty_out_of_range_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+
+    let result = contract.try_set_max_royalty(&admin, &9001);
+    assert_eq!(result, Err(Ok(Error::InvalidRoyalty)));
+}
+
+// ========== COLLECTION REGISTRY TESTS ==========
+
+#[test]
+fn test_blocked_collection_cannot_be_traded() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+
+    contract.set_collection_status(&admin, &nft.address, &Some(CollectionStatus::Blocked));
+    assert_eq!(contract.get_collection_status(&nft.address), Some(CollectionStatus::Blocked));
+
+    let result = contract.try_create_listing(&nft.address, &2, &seller, &100_0000000, &None, &None);
+    assert_eq!(result, Err(Ok(Error::CollectionBlocked)));
+
+    let result = contract.try_make_offer(&nft.address, &2, &buyer, &50_0000000, &None);
+    assert_eq!(result, Err(Ok(Error::CollectionBlocked)));
+
+    // Listings created before the block can't be bought either
+    let result = contract.try_buy(&listing_id, &buyer, &None);
+    assert_eq!(result, Err(Ok(Error::CollectionBlocked)));
+}
+
+#[test]
+fn test_allowlist_only_trading_mode() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+    let verified = create_mock_nft(&env);
+    let unknown = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+
+    verified.mock_mint(&seller, &1);
+    unknown.mock_mint(&seller, &1);
+
+    contract.set_collection_status(&admin, &verified.address, &Some(CollectionStatus::Verified));
+    contract.set_trading_mode(&admin, &TradingMode::AllowlistOnly);
+    assert_eq!(contract.get_trading_mode(), TradingMode::AllowlistOnly);
+    assert!(contract.is_verified_collection(&verified.address));
+    assert!(!contract.is_verified_collection(&unknown.address));
+
+    contract.create_listing(&verified.address, &1, &seller, &100_0000000, &None, &None);
+
+    let result = contract.try_create_listing(&unknown.address, &1, &seller, &100_0000000, &None, &None);
+    assert_eq!(result, Err(Ok(Error::CollectionNotAllowed)));
+}
+
+#[test]
+fn test_collection_fee_override() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+
+    // 1% instead of the global 2.5%
+    contract.set_collection_fee(&admin, &nft.address, &Some(100));
+    assert_eq!(contract.get_collection_fee(&nft.address), Some(100));
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.buy(&listing_id, &buyer, &None);
+
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 1_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 99_0000000);
+
+    let result = contract.try_set_collection_fee(&admin, &nft.address, &Some(1001));
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+}
+
+#[test]
+fn test_set_collection_status_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let not_admin = Address::generate(&env);
+    let nft_contract = Address::generate(&env);
+
+    let result = contract.try_set_collection_status(&not_admin, &nft_contract, &Some(CollectionStatus::Verified));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+// ========== TIERED FEE TESTS ==========
+
+#[test]
+fn test_seller_volume_tier() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+
+    // 1% after the first sale
+    contract.set_fee_tiers(&admin, &Vec::from_array(&env, [FeeTier { min_sales: 1, fee_bps: 100 }]));
+
+    let quote = contract.quote_fees(&nft.address, &1, &100_0000000, &seller);
+    assert_eq!(quote.platform_fee, 2_5000000);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.buy(&listing_id, &buyer, &None);
+    assert_eq!(contract.get_seller_sales(&seller), 1);
+
+    let quote = contract.quote_fees(&nft.address, &2, &100_0000000, &seller);
+    assert_eq!(quote.platform_fee, 1_0000000);
+    assert_eq!(quote.seller_proceeds, 99_0000000);
+}
+
+#[test]
+fn test_membership_discount_and_quote() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let membership = create_mock_nft(&env);
+    let member = Address::generate(&env);
+    let other = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    nft.mock_set_royalty(&artist, &500);
+    membership.mock_set_balance(&member, &1);
+
+    // Members pay half the fee
+    contract.set_membership_discount(&admin, &Some((membership.address.clone(), 5000)));
+
+    let quote = contract.quote_fees(&nft.address, &1, &100_0000000, &member);
+    assert_eq!(
+        quote,
+        FeeQuote {
+            platform_fee: 1_2500000,
+            referrer: None,
+            referral_fee: 0,
+            royalties: Vec::from_array(&env, [(artist.clone(), 5_0000000)]),
+            royalty_amount: 5_0000000,
+            seller_proceeds: 93_7500000,
+        }
+    );
+
+    let quote = contract.quote_fees(&nft.address, &1, &100_0000000, &other);
+    assert_eq!(quote.platform_fee, 2_5000000);
+}
+
+#[test]
+fn test_set_fee_tiers_invalid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+
+    // Not ascending
+    let tiers = Vec::from_array(
+        &env,
+        [FeeTier { min_sales: 10, fee_bps: 200 }, FeeTier { min_sales: 5, fee_bps: 100 }],
+    );
+    assert_eq!(contract.try_set_fee_tiers(&admin, &tiers), Err(Ok(Error::InvalidFee)));
+
+    // Above the 10% cap
+    let tiers = Vec::from_array(&env, [FeeTier { min_sales: 1, fee_bps: 1500 }]);
+    assert_eq!(contract.try_set_fee_tiers(&admin, &tiers), Err(Ok(Error::InvalidFee)));
+}
+
+// ========== REFERRAL TESTS ==========
+
+#[test]
+fn test_buy_with_registered_referrer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let partner = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+
+    // Partners get 20% of the platform fee
+    contract.set_referral_share(&admin, &2000);
+    contract.register_referrer(&admin, &partner, &None);
+    assert_eq!(contract.get_referrer_share(&partner), Some(2000));
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.buy(&listing_id, &buyer, &Some(partner.clone()));
+
+    // 2.5 XLM fee split 2.0 / 0.5, seller unaffected
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &partner), 5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 97_5000000);
+}
+
+#[test]
+fn test_referrer_share_override_and_unregistered() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let partner = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    contract.set_referral_share(&admin, &2000);
+    contract.register_referrer(&admin, &partner, &Some(5000));
+    assert_eq!(contract.get_referrer_share(&stranger), None);
+
+    // Unregistered referrers receive nothing
+    let listing_1 = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.buy(&listing_1, &buyer, &Some(stranger.clone()));
+    assert_eq!(token_balance(&env, &xlm.address, &stranger), 0);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_5000000);
+
+    // Override: half the fee
+    let listing_2 = contract.create_listing(&nft.address, &2, &seller, &100_0000000, &None, &None);
+    contract.batch_buy(&Vec::from_array(&env, [listing_2]), &buyer, &100_0000000, &false, &Some(partner.clone()));
+    assert_eq!(token_balance(&env, &xlm.address, &partner), 1_2500000);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 3_7500000);
+
+    contract.remove_referrer(&admin, &partner);
+    assert_eq!(contract.get_referrer_share(&partner), None);
+}
+
+// ========== SETTLEMENT PREVIEW TESTS ==========
+
+#[test]
+fn test_preview_buy_matches_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_set_royalty(&artist, &500);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    let preview = contract.preview_buy(&listing_id);
+    assert_eq!(preview.price, 100_0000000);
+    assert_eq!(preview.payment_token, xlm.address);
+    assert_eq!(preview.fees.royalties, Vec::from_array(&env, [(artist.clone(), 5_0000000)]));
+
+    contract.buy(&listing_id, &buyer, &None);
+
+    assert_eq!(token_balance(&env, &xlm.address, &seller), preview.fees.seller_proceeds);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), preview.fees.platform_fee);
+    assert_eq!(token_balance(&env, &xlm.address, &artist), preview.fees.royalty_amount);
+
+    // Sold listings can no longer be previewed
+    assert_eq!(contract.try_preview_buy(&listing_id), Err(Ok(Error::ListingNotActive)));
+}
+
+#[test]
+fn test_preview_buy_expired_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &Some(1000), &None);
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+
+    assert_eq!(contract.try_preview_buy(&listing_id), Err(Ok(Error::ListingExpired)));
+}
+
+#[test]
+fn test_preview_accept_offer_matches_settlement() {
+    let env = Env::default();
+    // Buyer's payment auth is a non-root authorization within accept_offer
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let artist = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_set_royalty(&artist, &1000);
+    contract.set_collection_fee(&admin, &nft.address, &Some(100));
+
+    let offer_id = contract.make_offer(&nft.address, &1, &buyer, &50_0000000, &None);
+    let preview = contract.preview_accept_offer(&offer_id);
+    assert_eq!(preview.price, 50_0000000);
+    assert_eq!(preview.fees.platform_fee, 5000000);
+    assert_eq!(preview.fees.royalty_amount, 5_0000000);
+
+    contract.accept_offer(&offer_id, &seller, &None);
+
+    assert_eq!(token_balance(&env, &xlm.address, &seller), preview.fees.seller_proceeds);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), preview.fees.platform_fee);
+    assert_eq!(token_balance(&env, &xlm.address, &artist), preview.fees.royalty_amount);
+}
+
+#[test]
+fn test_preview_blocked_collection() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+
+    let listing_id = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    let offer_id = contract.make_offer(&nft.address, &1, &buyer, &50_0000000, &None);
+    contract.set_collection_status(&admin, &nft.address, &Some(CollectionStatus::Blocked));
+
+    assert_eq!(contract.try_preview_buy(&listing_id), Err(Ok(Error::CollectionBlocked)));
+    assert_eq!(contract.try_preview_accept_offer(&offer_id), Err(Ok(Error::CollectionBlocked)));
+}
+
+// ========== COUNTER-OFFER TESTS ==========
+
+#[test]
+fn test_counter_offer_accepted_by_buyer() {
+    let env = Env::default();
+    // Buyer's payment auth is a non-root authorization within accept_offer
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let offer_id = contract.make_offer(&nft.address, &1, &buyer, &50_0000000, &None);
+    let counter_id = contract.counter_offer(&offer_id, &seller, &80_0000000, &None);
+
+    let counter = contract.get_counter_offer(&counter_id);
+    assert_eq!(counter.thread_id, offer_id);
+    assert_eq!(counter.buyer, buyer);
+    assert_eq!(contract.get_offer_thread(&offer_id), Vec::from_array(&env, [counter_id]));
+
+    // Accepting escrows the difference and raises the offer
+    contract.accept_counter_offer(&counter_id, &buyer);
+
+    assert_eq!(contract.get_offer(&offer_id).amount, 80_0000000);
+    assert_eq!(contract.get_offer_escrow(&offer_id), 30_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &contract.address), 30_0000000);
+    assert!(contract.try_get_counter_offer(&counter_id).is_err());
+    assert_eq!(contract.get_offer_thread(&offer_id).len(), 0);
+
+    // The seller settles at the countered amount
+    contract.accept_offer(&offer_id, &seller, &None);
+
+    assert_eq!(nft.owner_of(&1), buyer);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 78_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 920_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &contract.address), 0);
+    assert_eq!(contract.get_offer_escrow(&offer_id), 0);
+    assert!(contract.try_get_offer(&offer_id).is_err());
+}
+
+#[test]
+fn test_counter_offer_escrow_refunded_on_cancel() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let offer_id = contract.make_offer(&nft.address, &1, &buyer, &50_0000000, &None);
+    let first = contract.counter_offer(&offer_id, &seller, &70_0000000, &None);
+    let second = contract.counter_offer(&offer_id, &seller, &60_0000000, &None);
+    contract.accept_counter_offer(&first, &buyer);
+
+    // Other counters were closed with the acceptance
+    assert!(contract.try_get_counter_offer(&second).is_err());
+    let third = contract.counter_offer(&offer_id, &seller, &75_0000000, &None);
+    contract.accept_counter_offer(&third, &buyer);
+    assert_eq!(contract.get_offer_escrow(&offer_id), 25_0000000);
+
+    contract.cancel_offer(&offer_id, &buyer);
+    assert_eq!(token_balance(&env, &xlm.address, &buyer), 1000_0000000);
+    assert_eq!(contract.get_offer_escrow(&offer_id), 0);
+}
+
+#[test]
+fn test_counter_offer_validation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let offer_id = contract.make_offer(&nft.address, &1, &buyer, &50_0000000, &None);
+
+    // Must be above the offer
+    let result = contract.try_counter_offer(&offer_id, &seller, &50_0000000, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidPrice)));
+
+    // Only the token owner can counter
+    let result = contract.try_counter_offer(&offer_id, &stranger, &80_0000000, &None);
+    assert_eq!(result, Err(Ok(Error::NotTokenOwner)));
+
+    // The buyer can't counter their own offer
+    let result = contract.try_counter_offer(&offer_id, &buyer, &80_0000000, &None);
+    assert_eq!(result, Err(Ok(Error::CannotBuyOwnListing)));
+
+    // Only the original buyer can accept
+    let counter_id = contract.counter_offer(&offer_id, &seller, &80_0000000, &Some(1000));
+    let result = contract.try_accept_counter_offer(&counter_id, &stranger);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+    let result = contract.try_accept_counter_offer(&counter_id, &buyer);
+    assert_eq!(result, Err(Ok(Error::OfferExpired)));
+}
+
+#[test]
+fn test_counter_offer_declined_and_thread_closed() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    let offer_id = contract.make_offer(&nft.address, &1, &buyer, &50_0000000, &None);
+    let first = contract.counter_offer(&offer_id, &seller, &90_0000000, &None);
+    let second = contract.counter_offer(&offer_id, &seller, &70_0000000, &None);
+
+    // Buyer declines the first counter; the second stays open
+    contract.cancel_counter_offer(&first, &buyer);
+    assert_eq!(contract.get_offer_thread(&offer_id), Vec::from_array(&env, [second]));
+
+    // Cancelling the offer removes the rest of the thread
+    contract.cancel_offer(&offer_id, &buyer);
+    assert!(contract.try_get_counter_offer(&second).is_err());
+    assert_eq!(contract.get_offer_thread(&offer_id).len(), 0);
+}
+
+// ========== SEALED-BID AUCTION TESTS ==========
+
+fn sealed_terms(pricing: AuctionPricing, unrevealed: UnrevealedPolicy) -> SealedAuctionTerms {
+    SealedAuctionTerms {
+        reserve_price: 50_0000000,
+        deposit: 10_0000000,
+        pricing,
+        unrevealed,
+        bid_end: 1000,
+        reveal_end: 2000,
+    }
+}
+
+fn commit_sealed_bid(
+    env: &Env,
+    contract: &MarketplaceContractClient,
+    auction_id: u64,
+    bidder: &Address,
+    amount: i128,
+) -> BytesN<32> {
+    let salt = BytesN::from_array(env, &[amount as u8; 32]);
+    let commitment = contract.get_bid_commitment(&amount, &salt, bidder);
+    contract.commit_bid(&auction_id, bidder, &commitment);
+    salt
+}
+
+#[test]
+fn test_sealed_auction_second_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+    for bidder in [&alice, &bob, &carol] {
+        xlm.mint(bidder, &1000_0000000);
+    }
+
+    let terms = sealed_terms(AuctionPricing::SecondPrice, UnrevealedPolicy::Forfeit);
+    let auction_id = contract.create_sealed_auction(&seller, &nft.address, &1, &terms);
+
+    let alice_salt = commit_sealed_bid(&env, &contract, auction_id, &alice, 100_0000000);
+    let bob_salt = commit_sealed_bid(&env, &contract, auction_id, &bob, 80_0000000);
+    commit_sealed_bid(&env, &contract, auction_id, &carol, 90_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &contract.address), 30_0000000);
+
+    // Reveals are only accepted after bidding closes
+    let result = contract.try_reveal_bid(&auction_id, &alice, &100_0000000, &alice_salt);
+    assert_eq!(result, Err(Ok(Error::WrongAuctionPhase)));
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    contract.reveal_bid(&auction_id, &alice, &100_0000000, &alice_salt);
+    contract.reveal_bid(&auction_id, &bob, &80_0000000, &bob_salt);
+    // Carol never reveals
+
+    env.ledger().with_mut(|li| li.timestamp = 2001);
+    assert_eq!(contract.settle_sealed_auction(&auction_id), AuctionStatus::Settled);
+
+    // Alice wins at Bob's price: 80 - 2 fee
+    assert_eq!(nft.owner_of(&1), alice);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 78_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_0000000);
+
+    assert_eq!(contract.release_bid(&auction_id, &alice), 20_0000000);
+    assert_eq!(contract.release_bid(&auction_id, &bob), 80_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &bob), 1000_0000000);
+
+    // Carol's deposit goes to the seller
+    assert_eq!(contract.release_bid(&auction_id, &carol), 0);
+    assert_eq!(contract.claimable(&seller, &xlm.address), 10_0000000);
+    assert_eq!(contract.get_sealed_bid(&auction_id, &carol), None);
+}
+
+#[test]
+fn test_sealed_auction_first_price_and_invalid_reveal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+    xlm.mint(&alice, &1000_0000000);
+    xlm.mint(&bob, &1000_0000000);
+
+    let terms = sealed_terms(AuctionPricing::FirstPrice, UnrevealedPolicy::Refund);
+    let auction_id = contract.create_sealed_auction(&seller, &nft.address, &1, &terms);
+
+    let alice_salt = commit_sealed_bid(&env, &contract, auction_id, &alice, 70_0000000);
+    commit_sealed_bid(&env, &contract, auction_id, &bob, 90_0000000);
+
+    let result = contract.try_commit_bid(&auction_id, &alice, &BytesN::from_array(&env, &[0; 32]));
+    assert_eq!(result, Err(Ok(Error::AlreadyCommitted)));
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+
+    // Revealing a different amount than committed fails
+    let result = contract.try_reveal_bid(&auction_id, &alice, &95_0000000, &alice_salt);
+    assert_eq!(result, Err(Ok(Error::InvalidReveal)));
+    contract.reveal_bid(&auction_id, &alice, &70_0000000, &alice_salt);
+
+    // Settlement waits for the reveal window to close
+    assert_eq!(contract.try_settle_sealed_auction(&auction_id), Err(Ok(Error::WrongAuctionPhase)));
+
+    env.ledger().with_mut(|li| li.timestamp = 2001);
+    contract.settle_sealed_auction(&auction_id);
+
+    // Alice pays her own bid; Bob's unrevealed deposit is refunded
+    assert_eq!(nft.owner_of(&1), alice);
+    assert_eq!(token_balance(&env, &xlm.address, &alice), 930_0000000);
+    assert_eq!(contract.release_bid(&auction_id, &bob), 10_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &bob), 1000_0000000);
+}
+
+#[test]
+fn test_sealed_auction_reserve_not_met() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let alice = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+    xlm.mint(&alice, &1000_0000000);
+
+    let terms = sealed_terms(AuctionPricing::SecondPrice, UnrevealedPolicy::Forfeit);
+    let auction_id = contract.create_sealed_auction(&seller, &nft.address, &1, &terms);
+
+    let salt = commit_sealed_bid(&env, &contract, auction_id, &alice, 40_0000000);
+
+    // Bids can't be released while the auction is running
+    assert_eq!(contract.try_release_bid(&auction_id, &alice), Err(Ok(Error::WrongAuctionPhase)));
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    contract.reveal_bid(&auction_id, &alice, &40_0000000, &salt);
+
+    env.ledger().with_mut(|li| li.timestamp = 2001);
+    assert_eq!(contract.settle_sealed_auction(&auction_id), AuctionStatus::Settled);
+    assert_eq!(contract.get_sealed_auction(&auction_id).highest_bidder, None);
+
+    assert_eq!(nft.owner_of(&1), seller);
+    assert_eq!(contract.release_bid(&auction_id, &alice), 40_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &alice), 1000_0000000);
+}
+
+#[test]
+fn test_sealed_auction_cancelled_when_royalty_lookup_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+    xlm.mint(&alice, &1000_0000000);
+    xlm.mint(&bob, &1000_0000000);
+
+    let terms = sealed_terms(AuctionPricing::SecondPrice, UnrevealedPolicy::Forfeit);
+    let auction_id = contract.create_sealed_auction(&seller, &nft.address, &1, &terms);
+
+    let alice_salt = commit_sealed_bid(&env, &contract, auction_id, &alice, 100_0000000);
+    let bob_salt = commit_sealed_bid(&env, &contract, auction_id, &bob, 80_0000000);
+
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    contract.reveal_bid(&auction_id, &alice, &100_0000000, &alice_salt);
+    contract.reveal_bid(&auction_id, &bob, &80_0000000, &bob_salt);
+
+    // The collection raises its royalty above the marketplace max before settlement
+    nft.mock_set_royalty(&artist, &2000);
+
+    env.ledger().with_mut(|li| li.timestamp = 2001);
+    assert_eq!(contract.settle_sealed_auction(&auction_id), AuctionStatus::Cancelled);
+
+    // Nothing was paid or delivered, and every bid can be released in full
+    assert_eq!(nft.owner_of(&1), seller);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 0);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 0);
+    assert_eq!(contract.release_bid(&auction_id, &alice), 100_0000000);
+    assert_eq!(contract.release_bid(&auction_id, &bob), 80_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &contract.address), 0);
+}
+
+#[test]
+fn test_cancel_sealed_auction() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let alice = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+    nft.mock_approve_all(&seller, &contract.address);
+    xlm.mint(&alice, &1000_0000000);
+
+    let terms = sealed_terms(AuctionPricing::FirstPrice, UnrevealedPolicy::Refund);
+    let quiet = contract.create_sealed_auction(&seller, &nft.address, &1, &terms);
+    contract.cancel_sealed_auction(&quiet, &seller);
+    assert_eq!(contract.get_sealed_auction(&quiet).status, AuctionStatus::Cancelled);
+
+    let busy = contract.create_sealed_auction(&seller, &nft.address, &2, &terms);
+    commit_sealed_bid(&env, &contract, busy, &alice, 60_0000000);
+    assert_eq!(contract.try_cancel_sealed_auction(&busy, &seller), Err(Ok(Error::AuctionHasBids)));
+}
+
+// ========== RAFFLE TESTS ==========
+
+fn raffle_terms(max_tickets: u32, min_tickets: u32) -> RaffleTerms {
+    RaffleTerms {
+        ticket_price: 5_0000000,
+        max_tickets,
+        min_tickets,
+        end_time: 1000,
+    }
+}
+
+#[test]
+fn test_raffle_drawn_when_sold_out() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+    xlm.mint(&alice, &1000_0000000);
+    xlm.mint(&bob, &1000_0000000);
+
+    let raffle_id = contract.create_raffle(&seller, &nft.address, &1, &raffle_terms(20, 10));
+    assert_eq!(nft.owner_of(&1), contract.address);
+
+    contract.buy_tickets(&raffle_id, &alice, &15);
+    assert_eq!(contract.try_draw_raffle(&raffle_id), Err(Ok(Error::RaffleNotEnded)));
+
+    assert_eq!(contract.try_buy_tickets(&raffle_id, &bob, &6), Err(Ok(Error::RaffleSoldOut)));
+    assert_eq!(contract.try_buy_tickets(&raffle_id, &bob, &u32::MAX), Err(Ok(Error::RaffleSoldOut)));
+    contract.buy_tickets(&raffle_id, &bob, &5);
+    assert_eq!(contract.get_raffle_tickets(&raffle_id, &alice), 15);
+
+    // Sold out: can be drawn before the end time
+    let winner = contract.draw_raffle(&raffle_id).unwrap();
+    assert!(winner == alice || winner == bob);
+    assert_eq!(nft.owner_of(&1), winner);
+
+    // 100 XLM of tickets, 2.5 XLM fee
+    let raffle = contract.get_raffle(&raffle_id);
+    assert_eq!(raffle.status, RaffleStatus::Drawn);
+    assert_eq!(token_balance(&env, &xlm.address, &seller), 97_5000000);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_5000000);
+
+    assert_eq!(contract.try_refund_tickets(&raffle_id, &bob), Err(Ok(Error::NothingToWithdraw)));
+}
+
+#[test]
+fn test_raffle_refunded_below_minimum() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let alice = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+    xlm.mint(&alice, &1000_0000000);
+
+    let raffle_id = contract.create_raffle(&seller, &nft.address, &1, &raffle_terms(20, 10));
+    contract.buy_tickets(&raffle_id, &alice, &4);
+
+    env.ledger().with_mut(|li| li.timestamp = 1001);
+    assert_eq!(contract.try_buy_tickets(&raffle_id, &alice, &1), Err(Ok(Error::RaffleClosed)));
+
+    assert_eq!(contract.draw_raffle(&raffle_id), None);
+    assert_eq!(contract.get_raffle(&raffle_id).status, RaffleStatus::Cancelled);
+    assert_eq!(nft.owner_of(&1), seller);
+
+    assert_eq!(contract.refund_tickets(&raffle_id, &alice), 20_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &alice), 1000_0000000);
+    assert_eq!(contract.try_refund_tickets(&raffle_id, &alice), Err(Ok(Error::NothingToWithdraw)));
+}
+
+#[test]
+fn test_raffle_cancelled_when_royalty_lookup_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let artist = Address::generate(&env);
+    let alice = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+    xlm.mint(&alice, &1000_0000000);
+
+    let raffle_id = contract.create_raffle(&seller, &nft.address, &1, &raffle_terms(20, 10));
+    contract.buy_tickets(&raffle_id, &alice, &20);
+
+    // The collection raises its royalty above the marketplace max before the draw
+    nft.mock_set_royalty(&artist, &2000);
+
+    assert_eq!(contract.draw_raffle(&raffle_id), None);
+    let raffle = contract.get_raffle(&raffle_id);
+    assert_eq!(raffle.status, RaffleStatus::Cancelled);
+    assert_eq!(raffle.winner, None);
+
+    // The token goes back to the seller and tickets are refunded
+    assert_eq!(nft.owner_of(&1), seller);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 0);
+    assert_eq!(contract.refund_tickets(&raffle_id, &alice), 100_0000000);
+    assert_eq!(token_balance(&env, &xlm.address, &alice), 1000_0000000);
+}
+
+#[test]
+fn test_create_raffle_invalid_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _, _, _) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+
+    nft.mock_mint(&seller, &1);
+    nft.mock_approve_all(&seller, &contract.address);
+
+    // Minimum above the cap
+    let result = contract.try_create_raffle(&seller, &nft.address, &1, &raffle_terms(10, 11));
+    assert_eq!(result, Err(Ok(Error::InvalidRaffle)));
+
+    // Cap above the global maximum
+    let result = contract.try_create_raffle(&seller, &nft.address, &1, &raffle_terms(1001, 1));
+    assert_eq!(result, Err(Ok(Error::InvalidRaffle)));
+}
+
+// ========== GOVERNANCE TESTS ==========
+
+fn enable_two_of_three(
+    env: &Env,
+    contract: &MarketplaceContractClient,
+    admin: &Address,
+) -> (Address, Address, Address) {
+    let signers = (Address::generate(env), Address::generate(env), Address::generate(env));
+    let config = GovernanceConfig {
+        signers: Vec::from_array(env, [signers.0.clone(), signers.1.clone(), signers.2.clone()]),
+        threshold: 2,
+        delay: 3600,
+    };
+    contract.enable_governance(admin, &config);
+    assert_eq!(contract.get_governance(), Some(config));
+
+    signers
+}
+
+#[test]
+fn test_governance_proposal_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+    let (alice, bob, _) = enable_two_of_three(&env, &contract, &admin);
+
+    // The admin key alone can no longer change the fee
+    assert_eq!(contract.try_set_platform_fee(&admin, &100), Err(Ok(Error::Unauthorized)));
+
+    let proposal_id = contract.propose(&alice, &AdminAction::SetPlatformFee(100));
+    assert_eq!(contract.get_proposal(&proposal_id).executable_at, None);
+    assert_eq!(contract.try_execute_proposal(&proposal_id), Err(Ok(Error::InvalidProposal)));
+
+    // Second approval starts the timelock
+    contract.approve_proposal(&bob, &proposal_id);
+    assert_eq!(contract.get_proposal(&proposal_id).executable_at, Some(3600));
+    assert_eq!(contract.try_execute_proposal(&proposal_id), Err(Ok(Error::InvalidProposal)));
+
+    env.ledger().with_mut(|li| li.timestamp = 3600);
+    contract.execute_proposal(&proposal_id);
+
+    assert_eq!(contract.get_platform_fee(), 100);
+    assert_eq!(contract.get_proposal(&proposal_id).status, ProposalStatus::Executed);
+    assert_eq!(contract.try_execute_proposal(&proposal_id), Err(Ok(Error::InvalidProposal)));
+}
+
+#[test]
+fn test_governance_cancel_and_signer_checks() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+    let (alice, bob, carol) = enable_two_of_three(&env, &contract, &admin);
+    let stranger = Address::generate(&env);
+    let new_recipient = Address::generate(&env);
+
+    let action = AdminAction::SetFeeRecipient(new_recipient);
+    assert_eq!(contract.try_propose(&stranger, &action), Err(Ok(Error::Unauthorized)));
+
+    let proposal_id = contract.propose(&alice, &action);
+    assert_eq!(contract.try_approve_proposal(&alice, &proposal_id), Err(Ok(Error::InvalidProposal)));
+    assert_eq!(contract.try_approve_proposal(&stranger, &proposal_id), Err(Ok(Error::Unauthorized)));
+
+    contract.cancel_proposal(&carol, &proposal_id);
+    assert_eq!(contract.get_proposal(&proposal_id).status, ProposalStatus::Cancelled);
+    assert_eq!(contract.try_approve_proposal(&bob, &proposal_id), Err(Ok(Error::InvalidProposal)));
+}
+
+#[test]
+fn test_governance_disabled_by_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+    let (alice, bob, carol) = enable_two_of_three(&env, &contract, &admin);
+
+    // Fully approved, but not executed before governance is disabled
+    let stale_id = contract.propose(&alice, &AdminAction::SetPlatformFee(900));
+    contract.approve_proposal(&bob, &stale_id);
+
+    let proposal_id = contract.propose(&alice, &AdminAction::DisableGovernance);
+    contract.approve_proposal(&bob, &proposal_id);
+    env.ledger().with_mut(|li| li.timestamp = 3600);
+    contract.execute_proposal(&proposal_id);
+
+    assert_eq!(contract.get_governance(), None);
+    contract.set_platform_fee(&admin, &200);
+    assert_eq!(contract.get_platform_fee(), 200);
+
+    // Re-enabling doesn't revive proposals made under the old config
+    let config = GovernanceConfig {
+        signers: Vec::from_array(&env, [alice, bob.clone(), carol]),
+        threshold: 2,
+        delay: 3600,
+    };
+    contract.enable_governance(&admin, &config);
+
+    assert_eq!(contract.get_proposal(&stale_id).status, ProposalStatus::Cancelled);
+    assert_eq!(contract.try_execute_proposal(&stale_id), Err(Ok(Error::InvalidProposal)));
+    assert_eq!(contract.try_approve_proposal(&bob, &stale_id), Err(Ok(Error::InvalidProposal)));
+    assert_eq!(contract.get_platform_fee(), 200);
+}
+
+#[test]
+fn test_enable_governance_invalid_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+    let signer = Address::generate(&env);
+
+    // Threshold above the number of signers
+    let config = GovernanceConfig {
+        signers: Vec::from_array(&env, [signer.clone()]),
+        threshold: 2,
+        delay: 0,
+    };
+    assert_eq!(contract.try_enable_governance(&admin, &config), Err(Ok(Error::InvalidGovernance)));
+
+    // Duplicate signers
+    let config = GovernanceConfig {
+        signers: Vec::from_array(&env, [signer.clone(), signer]),
+        threshold: 1,
+        delay: 0,
+    };
+    assert_eq!(contract.try_enable_governance(&admin, &config), Err(Ok(Error::InvalidGovernance)));
+}
+
+// ========== SCHEDULED FEE TESTS ==========
+
+const WEEK: u64 = 7 * 24 * 60 * 60;
+
+#[test]
+fn test_scheduled_fee_change_applies_at_effective_time() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+
+    contract.schedule_fee_change(&admin, &350, &WEEK);
+    assert_eq!(
+        contract.get_scheduled_fee_change(),
+        Some(ScheduledFeeChange { fee_bps: 350, effective_at: WEEK })
+    );
+    assert_eq!(contract.get_platform_fee(), 250);
+
+    env.ledger().with_mut(|li| li.timestamp = WEEK);
+    assert_eq!(contract.get_platform_fee(), 350);
+    assert_eq!(contract.get_scheduled_fee_change(), None);
+
+    // The next change must wait out the 30-day period
+    let result = contract.try_schedule_fee_change(&admin, &450, &(2 * WEEK));
+    assert_eq!(result, Err(Ok(Error::InvalidExpiry)));
+
+    contract.schedule_fee_change(&admin, &450, &(WEEK + 30 * 24 * 60 * 60));
+    contract.cancel_fee_change(&admin);
+    assert_eq!(contract.get_scheduled_fee_change(), None);
+}
+
+#[test]
+fn test_schedule_fee_change_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, _, _) = setup_marketplace_with_token(&env);
+
+    // Too little notice
+    let result = contract.try_schedule_fee_change(&admin, &300, &(WEEK - 1));
+    assert_eq!(result, Err(Ok(Error::InvalidExpiry)));
+
+    // Moves more than 1% in one step
+    let result = contract.try_schedule_fee_change(&admin, &351, &WEEK);
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+    let result = contract.try_schedule_fee_change(&admin, &149, &WEEK);
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+
+    let not_admin = Address::generate(&env);
+    let result = contract.try_schedule_fee_change(&not_admin, &300, &WEEK);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Lowering the fee directly drops the pending change
+    contract.schedule_fee_change(&admin, &300, &WEEK);
+    contract.set_platform_fee(&admin, &200);
+    assert_eq!(contract.get_scheduled_fee_change(), None);
+}
+
+#[test]
+fn test_listing_settles_at_locked_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+
+    // Listed at 2.5%, sold after the fee rose to 3.5%
+    let old_listing = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.schedule_fee_change(&admin, &350, &WEEK);
+    env.ledger().with_mut(|li| li.timestamp = WEEK);
+    let new_listing = contract.create_listing(&nft.address, &2, &seller, &100_0000000, &None, &None);
+
+    assert_eq!(contract.get_listing(&old_listing).platform_fee_bps, Vec::from_array(&env, [250]));
+    assert_eq!(contract.preview_buy(&old_listing).fees.platform_fee, 2_5000000);
+
+    contract.buy(&old_listing, &buyer, &None);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 2_5000000);
+
+    contract.buy(&new_listing, &buyer, &None);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 6_0000000);
+}
+
+#[test]
+fn test_listing_settles_at_locked_collection_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin, fee_recipient, xlm) = setup_marketplace_with_token(&env);
+    let nft = create_mock_nft(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    xlm.mint(&buyer, &1000_0000000);
+    nft.mock_mint(&seller, &1);
+    nft.mock_mint(&seller, &2);
+    contract.set_collection_fee(&admin, &nft.address, &Some(100));
+
+    // Raising the override, or clearing it back to the 2.5% global fee,
+    // needs notice
+    let result = contract.try_set_collection_fee(&admin, &nft.address, &Some(200));
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+    let result = contract.try_set_collection_fee(&admin, &nft.address, &None);
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+    let result = contract.try_schedule_collection_fee_change(&admin, &nft.address, &201, &WEEK);
+    assert_eq!(result, Err(Ok(Error::InvalidFee)));
+
+    // Listed at 1%, sold after the collection fee rose to 2%
+    let old_listing = contract.create_listing(&nft.address, &1, &seller, &100_0000000, &None, &None);
+    contract.schedule_collection_fee_change(&admin, &nft.address, &200, &WEEK);
+    assert_eq!(
+        contract.get_collection_fee_change(&nft.address),
+        Some(ScheduledFeeChange { fee_bps: 200, effective_at: WEEK })
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = WEEK);
+    assert_eq!(contract.get_collection_fee(&nft.address), Some(200));
+    assert_eq!(contract.get_collection_fee_change(&nft.address), None);
+    let new_listing = contract.create_listing(&nft.address, &2, &seller, &100_0000000, &None, &None);
+
+    assert_eq!(contract.get_listing(&old_listing).platform_fee_bps, Vec::from_array(&env, [100]));
+
+    contract.buy(&old_listing, &buyer, &None);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 1_0000000);
+
+    contract.buy(&new_listing, &buyer, &None);
+    assert_eq!(token_balance(&env, &xlm.address, &fee_recipient), 3_0000000);
+}
@@ -1,3 +1,4 @@
+use nuna_governance::GovernanceError;
 use soroban_sdk::contracterror;
 
 #[contracterror]
@@ -16,6 +17,7 @@ pub enum Error {
     // Authorization errors
     Unauthorized = 20,
     NotApproved = 21,
+    OperatorNotAllowed = 22,
 
     // Validation errors
     InvalidAddress = 30,
@@ -29,9 +31,27 @@ pub enum Error {
     BatchTooLarge = 50,
     BatchEmpty = 51,
     InvalidBatchSize = 52,
+    DuplicateTokenId = 53,
 
     // Signature errors
     SignatureExpired = 60,
     InvalidNonce = 61,
     InvalidSignature = 62,
+
+    // Governance errors
+    InvalidGovernance = 70,
+    InvalidProposal = 71,
+
+    // Safe transfer errors
+    TransferRejected = 80,
+}
+
+impl From<GovernanceError> for Error {
+    fn from(err: GovernanceError) -> Self {
+        match err {
+            GovernanceError::Unauthorized => Error::Unauthorized,
+            GovernanceError::InvalidGovernance => Error::InvalidGovernance,
+            GovernanceError::InvalidProposal => Error::InvalidProposal,
+        }
+    }
 }
@@ -1,4 +1,5 @@
-use soroban_sdk::{Address, Env, String, Symbol, symbol_short};
+use soroban_sdk::{Address, Env, String, Symbol, Vec, symbol_short};
+use crate::{AdminAction, GovernanceConfig};
 
 // Event topics
 const COLLECTION_CREATED: Symbol = symbol_short!("coll_new");
@@ -7,6 +8,17 @@ const TRANSFER: Symbol = symbol_short!("transfer");
 const APPROVAL: Symbol = symbol_short!("approve");
 const APPROVAL_ALL: Symbol = symbol_short!("appr_all");
 const BURN: Symbol = symbol_short!("burn");
+const MARKETPLACE_HOOK: Symbol = symbol_short!("mkt_hook");
+const OPERATOR_FILTER: Symbol = symbol_short!("op_filter");
+const OPERATOR_ALLOWED: Symbol = symbol_short!("op_allow");
+const BASE_URI_SET: Symbol = symbol_short!("base_uri");
+const ADMIN_TRANSFERRED: Symbol = symbol_short!("admin_xfr");
+const ROYALTY_SET: Symbol = symbol_short!("roy_set");
+const GOVERNANCE_SET: Symbol = symbol_short!("gov_set");
+const PROPOSAL_CREATED: Symbol = symbol_short!("prop_new");
+const PROPOSAL_APPROVED: Symbol = symbol_short!("prop_appr");
+const PROPOSAL_EXECUTED: Symbol = symbol_short!("prop_exec");
+const PROPOSAL_CANCELLED: Symbol = symbol_short!("prop_cnl");
 
 pub fn emit_collection_created(env: &Env, name: &String, symbol: &String, admin: &Address) {
     env.events().publish((COLLECTION_CREATED,), (name, symbol, admin));
@@ -31,3 +43,48 @@ pub fn emit_approval_for_all(env: &Env, owner: &Address, operator: &Address, app
 pub fn emit_burn(env: &Env, owner: &Address, token_id: u64) {
     env.events().publish((BURN, owner), token_id);
 }
+
+pub fn emit_marketplace_hook_set(env: &Env, marketplace: &Option<Address>) {
+    env.events().publish((MARKETPLACE_HOOK,), marketplace.clone());
+}
+
+pub fn emit_operator_filter_set(env: &Env, enabled: bool) {
+    env.events().publish((OPERATOR_FILTER,), enabled);
+}
+
+pub fn emit_operator_allowed(env: &Env, operator: &Address, allowed: bool) {
+    env.events().publish((OPERATOR_ALLOWED, operator), allowed);
+}
+
+pub fn emit_base_uri_set(env: &Env, base_uri: &String) {
+    env.events().publish((BASE_URI_SET,), base_uri.clone());
+}
+
+pub fn emit_admin_transferred(env: &Env, new_admin: &Address) {
+    env.events().publish((ADMIN_TRANSFERRED,), new_admin.clone());
+}
+
+/// `token_id` is None for the default royalty, empty `splits` means it was cleared
+pub fn emit_royalty_set(env: &Env, token_id: Option<u64>, splits: &Vec<(Address, u32)>) {
+    env.events().publish((ROYALTY_SET,), (token_id, splits.clone()));
+}
+
+pub fn emit_governance_set(env: &Env, config: &Option<GovernanceConfig>) {
+    env.events().publish((GOVERNANCE_SET,), config.clone());
+}
+
+pub fn emit_proposal_created(env: &Env, proposal_id: u64, proposer: &Address, action: &AdminAction) {
+    env.events().publish((PROPOSAL_CREATED, proposer), (proposal_id, action.clone()));
+}
+
+pub fn emit_proposal_approved(env: &Env, proposal_id: u64, signer: &Address, executable_at: Option<u64>) {
+    env.events().publish((PROPOSAL_APPROVED, signer), (proposal_id, executable_at));
+}
+
+pub fn emit_proposal_executed(env: &Env, proposal_id: u64) {
+    env.events().publish((PROPOSAL_EXECUTED,), proposal_id);
+}
+
+pub fn emit_proposal_cancelled(env: &Env, proposal_id: u64, signer: &Address) {
+    env.events().publish((PROPOSAL_CANCELLED, signer), proposal_id);
+}
@@ -6,7 +6,7 @@
 //! Supports minting, transfers, approvals, and metadata management.
 
 use soroban_sdk::{
-    contract, contractimpl, contracttype, token, Address, Env, String, Vec,
+    contract, contractimpl, contracttype, symbol_short, token, Address, Bytes, Env, String, Symbol, Vec,
 };
 
 mod storage;
@@ -18,6 +18,9 @@ mod signature;
 use storage::*;
 use events::*;
 use errors::Error;
+use nuna_governance as governance;
+
+pub use nuna_governance::{GovernanceConfig, ProposalStatus};
 
 #[contract]
 pub struct NFTContract;
@@ -31,6 +34,46 @@ pub struct TokenMetadata {
     pub metadata_uri: String,
 }
 
+/// Most tokens a batch operation can touch
+const MAX_BATCH_SIZE: u32 = 100;
+
+/// Value `on_nft_received` must return to accept a safe transfer
+pub const NFT_RECEIVED: Symbol = symbol_short!("nft_rcvd");
+
+/// Admin operation that can be applied directly or through a proposal
+/// Minting is not governed and stays with the admin key.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    SetBaseUri(String),
+    TransferAdmin(Address),
+    SetMarketplaceHook(Address),
+    ClearMarketplaceHook,
+    SetOperatorFilter(bool),
+    SetAllowedOperator(Address, bool),
+    SetDefaultRoyaltySplits(Vec<(Address, u32)>),
+    SetTokenRoyaltySplits(u64, Vec<(Address, u32)>),
+    DeleteDefaultRoyalty,
+    ResetTokenRoyalty(u64),
+    /// Change signers, threshold or delay
+    SetGovernance(GovernanceConfig),
+    /// Return control to the admin key
+    DisableGovernance,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub proposal_id: u64,
+    pub action: AdminAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+    pub status: ProposalStatus,
+    pub created_at: u64,
+    /// Set when the threshold is reached: reached time + delay
+    pub executable_at: Option<u64>,
+}
+
 #[contractimpl]
 impl NFTContract {
     /// Initialize the NFT collection
@@ -109,13 +152,8 @@ impl NFTContract {
         admin.require_auth();
 
         // Validate batch size
+        validate_batch_size(&token_ids)?;
         let count = token_ids.len();
-        if count == 0 {
-            return Err(Error::BatchEmpty);
-        }
-        if count > 100 {
-            return Err(Error::BatchTooLarge);
-        }
         if count != metadata_list.len() {
             return Err(Error::InvalidBatchSize);
         }
@@ -182,6 +220,8 @@ impl NFTContract {
 
         events::emit_transfer(&env, &from, &to, token_id);
 
+        notify_marketplace(&env, None, token_id, &from, Some(to));
+
         Ok(())
     }
 
@@ -209,6 +249,8 @@ impl NFTContract {
             return Err(Error::NotApproved);
         }
 
+        check_operator_allowed(&env, &spender)?;
+
         // Clear approval
         clear_token_approval(&env, token_id);
 
@@ -217,9 +259,41 @@ impl NFTContract {
 
         events::emit_transfer(&env, &from, &to, token_id);
 
+        notify_marketplace(&env, Some(&spender), token_id, &from, Some(to));
+
         Ok(())
     }
 
+    /// Transfer NFT, checking that a contract recipient can handle it
+    /// Contract recipients must implement `on_nft_received` and return
+    /// `NFT_RECEIVED`; otherwise the transfer fails with `TransferRejected`.
+    pub fn safe_transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_id: u64,
+        data: Bytes,
+    ) -> Result<(), Error> {
+        Self::transfer(env.clone(), from.clone(), to.clone(), token_id)?;
+
+        check_nft_received(&env, &from, &from, &to, token_id, &data)
+    }
+
+    /// Transfer NFT with approval, checking that a contract recipient can
+    /// handle it (see `safe_transfer`)
+    pub fn safe_transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u64,
+        data: Bytes,
+    ) -> Result<(), Error> {
+        Self::transfer_from(env.clone(), spender.clone(), from.clone(), to.clone(), token_id)?;
+
+        check_nft_received(&env, &spender, &from, &to, token_id, &data)
+    }
+
     /// Approve an address to transfer a specific token
     pub fn approve(
         env: Env,
@@ -235,6 +309,8 @@ impl NFTContract {
             return Err(Error::Unauthorized);
         }
 
+        check_operator_allowed(&env, &to)?;
+
         set_token_approval(&env, token_id, &to);
 
         events::emit_approval(&env, &owner, &to, token_id);
@@ -251,6 +327,11 @@ impl NFTContract {
     ) -> Result<(), Error> {
         owner.require_auth();
 
+        // Revoking is always allowed
+        if approved {
+            check_operator_allowed(&env, &operator)?;
+        }
+
         set_operator_approval(&env, &owner, &operator, approved);
 
         events::emit_approval_for_all(&env, &owner, &operator, approved);
@@ -275,12 +356,158 @@ impl NFTContract {
         // Remove token
         remove_token(&env, token_id, &owner)?;
 
-        // Update total supply
+        // Update balance and total supply
+        let balance = get_balance(&env, &owner);
+        set_balance(&env, &owner, balance - 1);
+
         let total_supply = get_total_supply(&env);
         set_total_supply(&env, total_supply - 1);
 
         events::emit_burn(&env, &owner, token_id);
 
+        notify_marketplace(&env, None, token_id, &owner, None);
+
+        Ok(())
+    }
+
+    // ========== BATCH OPERATIONS ==========
+    // Same checks and per-token events as the single-token functions, with
+    // balances and total supply written once per batch. Max 100 tokens.
+
+    /// Transfer multiple NFTs from one owner to the same recipient
+    pub fn batch_transfer(
+        env: Env,
+        from: Address,
+        to: Address,
+        token_ids: Vec<u64>,
+    ) -> Result<(), Error> {
+        validate_batch_size(&token_ids)?;
+
+        from.require_auth();
+
+        for token_id in token_ids.iter() {
+            // Verify ownership
+            let owner = get_token_owner(&env, token_id)?;
+            if owner != from {
+                return Err(Error::Unauthorized);
+            }
+
+            move_token(&env, &from, &to, token_id);
+
+            events::emit_transfer(&env, &from, &to, token_id);
+
+            notify_marketplace(&env, None, token_id, &from, Some(to.clone()));
+        }
+
+        update_balances(&env, &from, &to, token_ids.len() as u64);
+
+        Ok(())
+    }
+
+    /// Transfer multiple NFTs with approval
+    /// The spender needs approval (or operator rights) for every token.
+    pub fn batch_transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_ids: Vec<u64>,
+    ) -> Result<(), Error> {
+        validate_batch_size(&token_ids)?;
+
+        spender.require_auth();
+
+        check_operator_allowed(&env, &spender)?;
+
+        let is_operator = is_approved_for_all(&env, &from, &spender);
+
+        for token_id in token_ids.iter() {
+            // Verify ownership
+            let owner = get_token_owner(&env, token_id)?;
+            if owner != from {
+                return Err(Error::Unauthorized);
+            }
+
+            // Check approval
+            if !is_operator && get_token_approval(&env, token_id) != Some(spender.clone()) {
+                return Err(Error::NotApproved);
+            }
+
+            // Clear approval
+            clear_token_approval(&env, token_id);
+
+            move_token(&env, &from, &to, token_id);
+
+            events::emit_transfer(&env, &from, &to, token_id);
+
+            notify_marketplace(&env, Some(&spender), token_id, &from, Some(to.clone()));
+        }
+
+        update_balances(&env, &from, &to, token_ids.len() as u64);
+
+        Ok(())
+    }
+
+    /// Approve one address to transfer multiple tokens
+    pub fn batch_approve(
+        env: Env,
+        owner: Address,
+        to: Address,
+        token_ids: Vec<u64>,
+    ) -> Result<(), Error> {
+        validate_batch_size(&token_ids)?;
+
+        owner.require_auth();
+
+        check_operator_allowed(&env, &to)?;
+
+        for token_id in token_ids.iter() {
+            // Verify ownership
+            let token_owner = get_token_owner(&env, token_id)?;
+            if token_owner != owner {
+                return Err(Error::Unauthorized);
+            }
+
+            set_token_approval(&env, token_id, &to);
+
+            events::emit_approval(&env, &owner, &to, token_id);
+        }
+
+        Ok(())
+    }
+
+    /// Burn multiple NFTs
+    pub fn batch_burn(
+        env: Env,
+        owner: Address,
+        token_ids: Vec<u64>,
+    ) -> Result<(), Error> {
+        validate_batch_size(&token_ids)?;
+
+        owner.require_auth();
+
+        for token_id in token_ids.iter() {
+            // Verify ownership
+            let token_owner = get_token_owner(&env, token_id)?;
+            if token_owner != owner {
+                return Err(Error::Unauthorized);
+            }
+
+            remove_token(&env, token_id, &owner)?;
+
+            events::emit_burn(&env, &owner, token_id);
+
+            notify_marketplace(&env, None, token_id, &owner, None);
+        }
+
+        // Update storage once at the end
+        let count = token_ids.len() as u64;
+        let balance = get_balance(&env, &owner);
+        set_balance(&env, &owner, balance - count);
+
+        let total_supply = get_total_supply(&env);
+        set_total_supply(&env, total_supply - count);
+
         Ok(())
     }
 
@@ -406,6 +633,9 @@ impl NFTContract {
     }
 
     // ========== ADMIN FUNCTIONS ==========
+    // Admin functions (other than minting) apply an `AdminAction`. While
+    // governance is enabled they fail with `Unauthorized`; propose the
+    // action instead.
 
     /// Update base URI (admin only)
     pub fn set_base_uri(
@@ -413,16 +643,8 @@ impl NFTContract {
         admin: Address,
         new_base_uri: String,
     ) -> Result<(), Error> {
-        let current_admin = get_admin(&env)?;
-        if current_admin != admin {
-            return Err(Error::Unauthorized);
-        }
-
-        admin.require_auth();
-
-        update_base_uri(&env, &new_base_uri);
-
-        Ok(())
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetBaseUri(new_base_uri))
     }
 
     /// Transfer admin role
@@ -431,16 +653,64 @@ impl NFTContract {
         current_admin: Address,
         new_admin: Address,
     ) -> Result<(), Error> {
-        let admin = get_admin(&env)?;
-        if admin != current_admin {
-            return Err(Error::Unauthorized);
-        }
+        check_admin(&env, &current_admin)?;
+        apply_admin_action(&env, AdminAction::TransferAdmin(new_admin))
+    }
 
-        current_admin.require_auth();
+    /// Register a marketplace to be notified when tokens move (admin only)
+    /// The marketplace uses this to invalidate listings whose token was
+    /// transferred or burned outside of a sale. Pass None to disable.
+    pub fn set_marketplace_hook(
+        env: Env,
+        admin: Address,
+        marketplace: Option<Address>,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
 
-        set_admin(&env, &new_admin);
+        let action = match marketplace {
+            Some(marketplace) => AdminAction::SetMarketplaceHook(marketplace),
+            None => AdminAction::ClearMarketplaceHook,
+        };
+        apply_admin_action(&env, action)
+    }
 
-        Ok(())
+    /// Get the marketplace notified on transfers and burns
+    pub fn marketplace_hook(env: Env) -> Option<Address> {
+        get_marketplace_hook(&env)
+    }
+
+    // ========== OPERATOR FILTER ==========
+
+    /// Turn the operator filter on or off (admin only)
+    /// While enabled, only allowlisted operators can be approved or call
+    /// `transfer_from`, so sales go through marketplaces that pay royalties.
+    pub fn set_operator_filter(
+        env: Env,
+        admin: Address,
+        enabled: bool,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetOperatorFilter(enabled))
+    }
+
+    /// Add or remove an operator (e.g. a marketplace contract) from the
+    /// allowlist (admin only)
+    pub fn set_allowed_operator(
+        env: Env,
+        admin: Address,
+        operator: Address,
+        allowed: bool,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetAllowedOperator(operator, allowed))
+    }
+
+    pub fn operator_filter_enabled(env: Env) -> bool {
+        is_operator_filter_enabled(&env)
+    }
+
+    pub fn is_allowed_operator(env: Env, operator: Address) -> bool {
+        is_operator_allowed(&env, &operator)
     }
 
     // ========== ROYALTY FUNCTIONS (ERC-2981) ==========
@@ -454,21 +724,23 @@ impl NFTContract {
         receiver: Address,
         royalty_bps: u32,
     ) -> Result<(), Error> {
-        let current_admin = get_admin(&env)?;
-        if current_admin != admin {
-            return Err(Error::Unauthorized);
-        }
-
-        admin.require_auth();
-
-        // Validate royalty (max 10%)
-        if royalty_bps > 1000 {
-            return Err(Error::InvalidRoyalty);
-        }
-
-        set_default_royalty_info(&env, &receiver, royalty_bps);
+        Self::set_default_royalty_splits(
+            env.clone(),
+            admin,
+            Vec::from_array(&env, [(receiver, royalty_bps)]),
+        )
+    }
 
-        Ok(())
+    /// Set a default royalty shared between several receivers
+    /// splits: (receiver, bps) per receiver, max 10 receivers and
+    /// 1000 bps (10%) in total
+    pub fn set_default_royalty_splits(
+        env: Env,
+        admin: Address,
+        splits: Vec<(Address, u32)>,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetDefaultRoyaltySplits(splits))
     }
 
     /// Set specific royalty for a single token (overrides default)
@@ -479,50 +751,59 @@ impl NFTContract {
         receiver: Address,
         royalty_bps: u32,
     ) -> Result<(), Error> {
-        let current_admin = get_admin(&env)?;
-        if current_admin != admin {
-            return Err(Error::Unauthorized);
-        }
-
-        admin.require_auth();
-
-        // Verify token exists
-        if !token_exists(&env, token_id) {
-            return Err(Error::TokenNotFound);
-        }
-
-        // Validate royalty (max 10%)
-        if royalty_bps > 1000 {
-            return Err(Error::InvalidRoyalty);
-        }
-
-        set_token_royalty_info(&env, token_id, &receiver, royalty_bps);
+        Self::set_token_royalty_splits(
+            env.clone(),
+            admin,
+            token_id,
+            Vec::from_array(&env, [(receiver, royalty_bps)]),
+        )
+    }
 
-        Ok(())
+    /// Set a royalty split for a single token (overrides default)
+    pub fn set_token_royalty_splits(
+        env: Env,
+        admin: Address,
+        token_id: u64,
+        splits: Vec<(Address, u32)>,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetTokenRoyaltySplits(token_id, splits))
     }
 
     /// Get royalty information for a token sale (ERC-2981)
     /// Returns (receiver address, royalty amount)
+    /// For split royalties this is the primary (first) receiver and only its
+    /// own share; callers must use `royalty_splits` to pay every receiver.
     pub fn royalty_info(
         env: Env,
         token_id: u64,
         sale_price: i128,
     ) -> (Address, i128) {
-        // Try to get token-specific royalty first
-        if let Some((receiver, bps)) = get_token_royalty_info(&env, token_id) {
-            let royalty_amount = (sale_price * bps as i128) / 10000;
-            return (receiver, royalty_amount);
+        match Self::royalty_splits(env.clone(), token_id, sale_price).first() {
+            Some(primary) => primary,
+            // No royalty set
+            None => (env.current_contract_address(), 0),
         }
+    }
 
-        // Fall back to default royalty
-        if let Some((receiver, bps)) = get_default_royalty_info(&env) {
-            let royalty_amount = (sale_price * bps as i128) / 10000;
-            return (receiver, royalty_amount);
+    /// Get every royalty receiver and amount for a token sale
+    /// Returns an empty list if no royalty is set
+    pub fn royalty_splits(
+        env: Env,
+        token_id: u64,
+        sale_price: i128,
+    ) -> Vec<(Address, i128)> {
+        // Token-specific royalty first, then the collection default
+        let splits = get_token_royalty_info(&env, token_id)
+            .or_else(|| get_default_royalty_info(&env))
+            .unwrap_or(Vec::new(&env));
+
+        let mut amounts = Vec::new(&env);
+        for (receiver, bps) in splits.iter() {
+            amounts.push_back((receiver, (sale_price * bps as i128) / 10000));
         }
 
-        // No royalty set
-        let zero_address = env.current_contract_address();
-        (zero_address, 0)
+        amounts
     }
 
     /// Delete default royalty
@@ -530,16 +811,8 @@ impl NFTContract {
         env: Env,
         admin: Address,
     ) -> Result<(), Error> {
-        let current_admin = get_admin(&env)?;
-        if current_admin != admin {
-            return Err(Error::Unauthorized);
-        }
-
-        admin.require_auth();
-
-        delete_default_royalty_info(&env);
-
-        Ok(())
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::DeleteDefaultRoyalty)
     }
 
     /// Reset token-specific royalty (will fall back to default)
@@ -548,17 +821,254 @@ impl NFTContract {
         admin: Address,
         token_id: u64,
     ) -> Result<(), Error> {
-        let current_admin = get_admin(&env)?;
-        if current_admin != admin {
-            return Err(Error::Unauthorized);
-        }
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::ResetTokenRoyalty(token_id))
+    }
+    // ========== GOVERNANCE ==========
 
-        admin.require_auth();
+    /// Hand admin operations over to M-of-N signers with a timelock
+    /// Only the admin can enable it; afterwards it can only be changed or
+    /// disabled by a `SetGovernance` or `DisableGovernance` proposal.
+    pub fn enable_governance(
+        env: Env,
+        admin: Address,
+        config: GovernanceConfig,
+    ) -> Result<(), Error> {
+        check_admin(&env, &admin)?;
+        apply_admin_action(&env, AdminAction::SetGovernance(config))
+    }
 
-        delete_token_royalty_info(&env, token_id);
+    /// Propose an admin action (signers only). Counts as the proposer's
+    /// approval. Returns the proposal ID.
+    pub fn propose(
+        env: Env,
+        proposer: Address,
+        action: AdminAction,
+    ) -> Result<u64, Error> {
+        proposer.require_auth();
+
+        let config = get_governance(&env).ok_or(Error::Unauthorized)?;
+        governance::check_signer(&config, &proposer)?;
+
+        let proposal_id = get_and_increment_proposal_counter(&env);
+        let mut proposal = Proposal {
+            proposal_id,
+            action: action.clone(),
+            proposer: proposer.clone(),
+            approvals: Vec::new(&env),
+            status: ProposalStatus::Pending,
+            created_at: env.ledger().timestamp(),
+            executable_at: None,
+        };
+        governance::record_approval(&env, &config, &mut proposal.approvals, &mut proposal.executable_at, &proposer)?;
+
+        save_proposal(&env, &proposal);
+
+        events::emit_proposal_created(&env, proposal_id, &proposer, &action);
+
+        Ok(proposal_id)
+    }
+
+    /// Approve a pending proposal (signers only)
+    pub fn approve_proposal(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<(), Error> {
+        signer.require_auth();
+
+        let config = get_governance(&env).ok_or(Error::Unauthorized)?;
+        governance::check_signer(&config, &signer)?;
+
+        let mut proposal = get_proposal(&env, proposal_id)?;
+        governance::check_pending(&proposal.status)?;
+        governance::record_approval(&env, &config, &mut proposal.approvals, &mut proposal.executable_at, &signer)?;
+
+        save_proposal(&env, &proposal);
+
+        events::emit_proposal_approved(&env, proposal_id, &signer, proposal.executable_at);
 
         Ok(())
     }
+
+    /// Execute an approved proposal once its timelock has passed.
+    /// Callable by anyone.
+    pub fn execute_proposal(env: Env, proposal_id: u64) -> Result<(), Error> {
+        let config = get_governance(&env).ok_or(Error::Unauthorized)?;
+
+        let mut proposal = get_proposal(&env, proposal_id)?;
+        governance::check_executable(&env, &config, &proposal.status, &proposal.approvals, proposal.executable_at)?;
+
+        proposal.status = ProposalStatus::Executed;
+        save_proposal(&env, &proposal);
+
+        apply_admin_action(&env, proposal.action)?;
+
+        events::emit_proposal_executed(&env, proposal_id);
+
+        Ok(())
+    }
+
+    /// Cancel a pending proposal (any signer)
+    pub fn cancel_proposal(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<(), Error> {
+        signer.require_auth();
+
+        let config = get_governance(&env).ok_or(Error::Unauthorized)?;
+        governance::check_signer(&config, &signer)?;
+
+        let mut proposal = get_proposal(&env, proposal_id)?;
+        governance::check_pending(&proposal.status)?;
+
+        proposal.status = ProposalStatus::Cancelled;
+        save_proposal(&env, &proposal);
+
+        events::emit_proposal_cancelled(&env, proposal_id, &signer);
+
+        Ok(())
+    }
+
+    pub fn get_governance(env: Env) -> Option<GovernanceConfig> {
+        get_governance(&env)
+    }
+
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Result<Proposal, Error> {
+        get_proposal(&env, proposal_id)
+    }
+}
+
+/// Verify `admin` is the admin and may act directly (governance disabled)
+fn check_admin(env: &Env, admin: &Address) -> Result<(), Error> {
+    let current_admin = get_admin(env)?;
+    if current_admin != *admin {
+        return Err(Error::Unauthorized);
+    }
+
+    admin.require_auth();
+
+    if get_governance(env).is_some() {
+        return Err(Error::Unauthorized);
+    }
+
+    Ok(())
+}
+
+/// Validate and apply an admin action
+/// Shared by the direct admin functions and executed proposals.
+fn apply_admin_action(env: &Env, action: AdminAction) -> Result<(), Error> {
+    match action {
+        AdminAction::SetBaseUri(new_base_uri) => {
+            update_base_uri(env, &new_base_uri);
+
+            events::emit_base_uri_set(env, &new_base_uri);
+        }
+        AdminAction::TransferAdmin(new_admin) => {
+            set_admin(env, &new_admin);
+
+            events::emit_admin_transferred(env, &new_admin);
+        }
+        AdminAction::SetMarketplaceHook(marketplace) => {
+            let marketplace = Some(marketplace);
+            set_marketplace_hook(env, &marketplace);
+
+            events::emit_marketplace_hook_set(env, &marketplace);
+        }
+        AdminAction::ClearMarketplaceHook => {
+            set_marketplace_hook(env, &None);
+
+            events::emit_marketplace_hook_set(env, &None);
+        }
+        AdminAction::SetOperatorFilter(enabled) => {
+            set_operator_filter_enabled(env, enabled);
+
+            events::emit_operator_filter_set(env, enabled);
+        }
+        AdminAction::SetAllowedOperator(operator, allowed) => {
+            set_operator_allowed(env, &operator, allowed);
+
+            events::emit_operator_allowed(env, &operator, allowed);
+        }
+        AdminAction::SetDefaultRoyaltySplits(splits) => {
+            validate_royalty_splits(&splits)?;
+
+            set_default_royalty_info(env, &splits);
+
+            events::emit_royalty_set(env, None, &splits);
+        }
+        AdminAction::SetTokenRoyaltySplits(token_id, splits) => {
+            // Verify token exists
+            if !token_exists(env, token_id) {
+                return Err(Error::TokenNotFound);
+            }
+
+            validate_royalty_splits(&splits)?;
+
+            set_token_royalty_info(env, token_id, &splits);
+
+            events::emit_royalty_set(env, Some(token_id), &splits);
+        }
+        AdminAction::DeleteDefaultRoyalty => {
+            delete_default_royalty_info(env);
+
+            events::emit_royalty_set(env, None, &Vec::new(env));
+        }
+        AdminAction::ResetTokenRoyalty(token_id) => {
+            delete_token_royalty_info(env, token_id);
+
+            events::emit_royalty_set(env, Some(token_id), &Vec::new(env));
+        }
+        AdminAction::SetGovernance(config) => {
+            governance::validate_config(&config)?;
+
+            let config = Some(config);
+            set_governance(env, &config);
+
+            events::emit_governance_set(env, &config);
+        }
+        AdminAction::DisableGovernance => {
+            set_governance(env, &None);
+
+            events::emit_governance_set(env, &None);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reject operators missing from the allowlist while the filter is enabled
+fn check_operator_allowed(env: &Env, operator: &Address) -> Result<(), Error> {
+    if is_operator_filter_enabled(env) && !is_operator_allowed(env, operator) {
+        return Err(Error::OperatorNotAllowed);
+    }
+
+    Ok(())
+}
+
+/// Check a royalty split: 1-10 distinct receivers totalling at most
+/// 1000 bps (10%)
+fn validate_royalty_splits(splits: &Vec<(Address, u32)>) -> Result<(), Error> {
+    if splits.is_empty() || splits.len() > 10 {
+        return Err(Error::InvalidRoyalty);
+    }
+
+    let mut total_bps: u32 = 0;
+    for (index, (receiver, bps)) in splits.iter().enumerate() {
+        // Reject the same receiver appearing twice
+        if splits.iter().skip(index + 1).any(|(other, _)| other == receiver) {
+            return Err(Error::InvalidRoyalty);
+        }
+
+        total_bps = total_bps.saturating_add(bps);
+    }
+
+    if total_bps > 1000 {
+        return Err(Error::InvalidRoyalty);
+    }
+
+    Ok(())
 }
 
 /// Internal transfer helper
@@ -568,22 +1078,159 @@ fn transfer_token(
     to: &Address,
     token_id: u64,
 ) -> Result<(), Error> {
+    move_token(env, from, to, token_id);
+    update_balances(env, from, to, 1);
+
+    Ok(())
+}
+
+/// Update owner and enumeration; balances are left to the caller
+fn move_token(env: &Env, from: &Address, to: &Address, token_id: u64) {
     // Update owner
     set_token_owner(env, token_id, to);
 
-    // Update balances
+    // Update enumeration
+    remove_token_from_owner_enumeration(env, from, token_id);
+    add_token_to_owner_enumeration(env, to, token_id);
+}
+
+/// Move `count` tokens' worth of balance from `from` to `to`
+fn update_balances(env: &Env, from: &Address, to: &Address, count: u64) {
     let from_balance = get_balance(env, from);
-    set_balance(env, from, from_balance - 1);
+    set_balance(env, from, from_balance - count);
 
     let to_balance = get_balance(env, to);
-    set_balance(env, to, to_balance + 1);
+    set_balance(env, to, to_balance + count);
+}
 
-    // Update enumeration
-    remove_token_from_owner_enumeration(env, from, token_id);
-    add_token_to_owner_enumeration(env, to, token_id);
+/// Check a batch holds 1-100 distinct tokens
+/// Balances are updated once per batch, so a repeated ID (for example in a
+/// transfer to the same owner) would otherwise miscount them.
+fn validate_batch_size(token_ids: &Vec<u64>) -> Result<(), Error> {
+    if token_ids.is_empty() {
+        return Err(Error::BatchEmpty);
+    }
+    if token_ids.len() > MAX_BATCH_SIZE {
+        return Err(Error::BatchTooLarge);
+    }
+
+    for (index, token_id) in token_ids.iter().enumerate() {
+        if token_ids.first_index_of(token_id) != Some(index as u32) {
+            return Err(Error::DuplicateTokenId);
+        }
+    }
 
     Ok(())
 }
 
+/// Ask a contract recipient to acknowledge a safe transfer
+///
+/// Accounts can always receive tokens. Skipped when the recipient is the
+/// operator: it initiated the transfer and cannot be re-entered. A failed
+/// call or any other return value rejects the transfer.
+fn check_nft_received(
+    env: &Env,
+    operator: &Address,
+    from: &Address,
+    to: &Address,
+    token_id: u64,
+    data: &Bytes,
+) -> Result<(), Error> {
+    use nft_receiver::NftReceiverClient;
+
+    if !is_contract(to) || to == operator {
+        return Ok(());
+    }
+
+    let client = NftReceiverClient::new(env, to);
+    match client.try_on_nft_received(
+        operator,
+        from,
+        &token_id,
+        data,
+    ) {
+        Ok(Ok(ack)) if ack == NFT_RECEIVED => Ok(()),
+        _ => Err(Error::TransferRejected),
+    }
+}
+
+/// Whether `address` is a contract (strkey `C...`) rather than an account
+fn is_contract(address: &Address) -> bool {
+    let strkey = address.to_string();
+    let mut buf = [0u8; 56];
+    if strkey.len() as usize != buf.len() {
+        return false;
+    }
+
+    strkey.copy_into_slice(&mut buf);
+    buf[0] == b'C'
+}
+
+/// Tell the registered marketplace (if any) that a token moved
+///
+/// Skipped when the marketplace itself is the spender, since it invalidates
+/// listings for its own transfers and cannot be re-entered. Failures are
+/// ignored so a misbehaving marketplace can never block transfers.
+fn notify_marketplace(
+    env: &Env,
+    spender: Option<&Address>,
+    token_id: u64,
+    from: &Address,
+    to: Option<Address>,
+) {
+    use marketplace_hook::MarketplaceHookClient;
+
+    if let Some(marketplace) = get_marketplace_hook(env) {
+        // The marketplace already knows about moves it makes itself,
+        // including releasing tokens it holds in escrow
+        if spender == Some(&marketplace) || *from == marketplace {
+            return;
+        }
+
+        let client = MarketplaceHookClient::new(env, &marketplace);
+        let _ = client.try_on_token_moved(
+            &env.current_contract_address(),
+            &token_id,
+            from,
+            &to,
+        );
+    }
+}
+
+/// Interface implemented by marketplaces that want transfer notifications
+mod marketplace_hook {
+    use soroban_sdk::{contractclient, Address, Env};
+
+    #[contractclient(name = "MarketplaceHookClient")]
+    pub trait MarketplaceHookInterface {
+        /// Called after a transfer (to = new owner) or burn (to = None)
+        fn on_token_moved(
+            env: Env,
+            nft_contract: Address,
+            token_id: u64,
+            from: Address,
+            to: Option<Address>,
+        );
+    }
+}
+
+/// Interface implemented by contracts that accept safe transfers
+mod nft_receiver {
+    use soroban_sdk::{contractclient, Address, Bytes, Env, Symbol};
+
+    #[contractclient(name = "NftReceiverClient")]
+    pub trait NftReceiverInterface {
+        /// Called after a safe transfer to the implementing contract
+        /// Must return `NFT_RECEIVED` to accept the token.
+        fn on_nft_received(
+            env: Env,
+            operator: Address,
+            from: Address,
+            token_id: u64,
+            data: Bytes,
+        ) -> Symbol;
+    }
+}
+
 #[cfg(test)]
 mod test;
@@ -1,5 +1,5 @@
 use soroban_sdk::{Address, Env, String, Vec};
-use crate::{TokenMetadata, errors::Error};
+use crate::{GovernanceConfig, Proposal, TokenMetadata, errors::Error};
 
 // ============================================================================
 // TTL CONFIGURATION (Time To Live for State Archival)
@@ -31,6 +31,11 @@ const NAME_KEY: &str = "name";
 const SYMBOL_KEY: &str = "symbol";
 const BASE_URI_KEY: &str = "base_uri";
 const TOTAL_SUPPLY_KEY: &str = "total_supply";
+const MARKETPLACE_HOOK_KEY: &str = "mkt_hook";
+const OPERATOR_FILTER_KEY: &str = "op_filter";
+const GOVERNANCE_KEY: &str = "gov";
+const PROPOSAL_COUNTER_KEY: &str = "prop_cnt";
+const FIRST_CURRENT_PROPOSAL_KEY: &str = "prop_frst";
 
 // Dynamic keys helpers
 fn token_owner_key(token_id: u64) -> soroban_sdk::Symbol {
@@ -61,6 +66,14 @@ fn token_index_key(token_id: u64) -> soroban_sdk::Symbol {
     soroban_sdk::symbol_short!("tok_idx")
 }
 
+fn allowed_operator_key(_operator: &Address) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("op_allow")
+}
+
+fn proposal_key(_proposal_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("proposal")
+}
+
 // ============================================================================
 // TTL HELPER FUNCTIONS
 // ============================================================================
@@ -73,24 +86,23 @@ fn extend_instance_ttl(env: &Env) {
 }
 
 /// Extend TTL for NFT ownership and metadata
+/// Either entry may not exist yet while a token is being minted
 fn extend_nft_ttl(env: &Env, token_id: u64) {
     // Extend ownership
-    env.storage()
-        .persistent()
-        .extend_ttl(
-            &(token_owner_key(token_id), token_id),
-            NFT_LIFETIME_THRESHOLD,
-            NFT_BUMP_AMOUNT,
-        );
+    let owner_key = &(token_owner_key(token_id), token_id);
+    if env.storage().persistent().has(owner_key) {
+        env.storage()
+            .persistent()
+            .extend_ttl(owner_key, NFT_LIFETIME_THRESHOLD, NFT_BUMP_AMOUNT);
+    }
 
     // Extend metadata
-    env.storage()
-        .persistent()
-        .extend_ttl(
-            &(token_metadata_key(token_id), token_id),
-            NFT_LIFETIME_THRESHOLD,
-            NFT_BUMP_AMOUNT,
-        );
+    let metadata_key = &(token_metadata_key(token_id), token_id);
+    if env.storage().persistent().has(metadata_key) {
+        env.storage()
+            .persistent()
+            .extend_ttl(metadata_key, NFT_LIFETIME_THRESHOLD, NFT_BUMP_AMOUNT);
+    }
 }
 
 /// Extend TTL for approval (shorter lifetime)
@@ -135,6 +147,108 @@ pub fn get_admin(env: &Env) -> Result<Address, Error> {
         .ok_or(Error::NotInitialized)
 }
 
+// ========== MARKETPLACE HOOK ==========
+
+pub fn set_marketplace_hook(env: &Env, marketplace: &Option<Address>) {
+    match marketplace {
+        Some(address) => env.storage().instance().set(&MARKETPLACE_HOOK_KEY, address),
+        None => env.storage().instance().remove(&MARKETPLACE_HOOK_KEY),
+    }
+}
+
+pub fn get_marketplace_hook(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&MARKETPLACE_HOOK_KEY)
+}
+
+// ========== OPERATOR FILTER ==========
+
+pub fn set_operator_filter_enabled(env: &Env, enabled: bool) {
+    env.storage().instance().set(&OPERATOR_FILTER_KEY, &enabled);
+}
+
+pub fn is_operator_filter_enabled(env: &Env) -> bool {
+    env.storage().instance().get(&OPERATOR_FILTER_KEY).unwrap_or(false)
+}
+
+pub fn set_operator_allowed(env: &Env, operator: &Address, allowed: bool) {
+    let key = &(allowed_operator_key(operator), operator);
+    if allowed {
+        env.storage().persistent().set(key, &true);
+        env.storage()
+            .persistent()
+            .extend_ttl(key, NFT_LIFETIME_THRESHOLD, NFT_BUMP_AMOUNT);
+    } else {
+        env.storage().persistent().remove(key);
+    }
+}
+
+pub fn is_operator_allowed(env: &Env, operator: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get(&(allowed_operator_key(operator), operator))
+        .unwrap_or(false)
+}
+
+// ========== GOVERNANCE ==========
+
+pub fn get_governance(env: &Env) -> Option<GovernanceConfig> {
+    env.storage().instance().get(&GOVERNANCE_KEY)
+}
+
+/// None disables governance
+/// Proposals made before the change are cancelled (see `get_proposal`).
+pub fn set_governance(env: &Env, config: &Option<GovernanceConfig>) {
+    match config {
+        Some(config) => env.storage().instance().set(&GOVERNANCE_KEY, config),
+        None => env.storage().instance().remove(&GOVERNANCE_KEY),
+    }
+
+    let next_proposal_id: u64 = env.storage()
+        .instance()
+        .get(&PROPOSAL_COUNTER_KEY)
+        .unwrap_or(0);
+    env.storage().instance().set(&FIRST_CURRENT_PROPOSAL_KEY, &next_proposal_id);
+}
+
+pub fn get_and_increment_proposal_counter(env: &Env) -> u64 {
+    let counter: u64 = env.storage()
+        .instance()
+        .get(&PROPOSAL_COUNTER_KEY)
+        .unwrap_or(0);
+
+    env.storage().instance().set(&PROPOSAL_COUNTER_KEY, &(counter + 1));
+
+    counter
+}
+
+pub fn save_proposal(env: &Env, proposal: &Proposal) {
+    let key = &(proposal_key(proposal.proposal_id), proposal.proposal_id);
+    env.storage().persistent().set(key, proposal);
+    env.storage()
+        .persistent()
+        .extend_ttl(key, NFT_LIFETIME_THRESHOLD, NFT_BUMP_AMOUNT);
+}
+
+pub fn get_proposal(env: &Env, proposal_id: u64) -> Result<Proposal, Error> {
+    let key = &(proposal_key(proposal_id), proposal_id);
+    let mut proposal: Proposal = env.storage()
+        .persistent()
+        .get(key)
+        .ok_or(Error::InvalidProposal)?;
+
+    let first_current_id: u64 = env.storage()
+        .instance()
+        .get(&FIRST_CURRENT_PROPOSAL_KEY)
+        .unwrap_or(0);
+    proposal.status = nuna_governance::current_status(proposal.status, proposal_id, first_current_id);
+
+    env.storage()
+        .persistent()
+        .extend_ttl(key, NFT_LIFETIME_THRESHOLD, NFT_BUMP_AMOUNT);
+
+    Ok(proposal)
+}
+
 // ========== COLLECTION INFO ==========
 
 pub fn set_collection_info(env: &Env, name: &String, symbol: &String, base_uri: &String) {
@@ -239,6 +353,7 @@ pub fn get_token_metadata(env: &Env, token_id: u64) -> Result<TokenMetadata, Err
     Ok(metadata)
 }
 
+/// Remove a burned token; the caller updates the owner's balance
 pub fn remove_token(env: &Env, token_id: u64, owner: &Address) -> Result<(), Error> {
     // Remove from enumeration first
     remove_token_from_owner_enumeration(env, owner, token_id);
@@ -258,12 +373,6 @@ pub fn remove_token(env: &Env, token_id: u64, owner: &Address) -> Result<(), Err
         .persistent()
         .remove(&(token_approval_key(token_id), token_id));
 
-    // Update balance
-    let balance = get_balance(env, owner);
-    if balance > 0 {
-        set_balance(env, owner, balance - 1);
-    }
-
     Ok(())
 }
 
@@ -324,27 +433,46 @@ pub fn get_balance(env: &Env, owner: &Address) -> u64 {
 // ========== ROYALTIES (ERC-2981) ==========
 
 fn default_royalty_key() -> soroban_sdk::Symbol {
-    soroban_sdk::symbol_short!("def_roy")
+    soroban_sdk::symbol_short!("def_rsplt")
 }
 
 fn token_royalty_key(token_id: u64) -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("tok_rsplt")
+}
+
+// Releases before royalty splits stored a single (receiver, bps) under these keys.
+// They are still read as a one-element split and cleared on the next write.
+fn legacy_default_royalty_key() -> soroban_sdk::Symbol {
+    soroban_sdk::symbol_short!("def_roy")
+}
+
+fn legacy_token_royalty_key(_token_id: u64) -> soroban_sdk::Symbol {
     soroban_sdk::symbol_short!("tok_roy")
 }
 
 /// Set default royalty for entire collection
-/// Stores (receiver, bps) tuple
-pub fn set_default_royalty_info(env: &Env, receiver: &Address, bps: u32) {
+/// Stores a list of (receiver, bps) splits
+pub fn set_default_royalty_info(env: &Env, splits: &Vec<(Address, u32)>) {
     env.storage()
         .instance()
-        .set(&default_royalty_key(), &(receiver.clone(), bps));
+        .set(&default_royalty_key(), splits);
+    env.storage()
+        .instance()
+        .remove(&legacy_default_royalty_key());
 }
 
 /// Get default royalty info
-/// Returns Option<(receiver, bps)>
-pub fn get_default_royalty_info(env: &Env) -> Option<(Address, u32)> {
+/// Returns Option<Vec<(receiver, bps)>>, falling back to the legacy single receiver
+pub fn get_default_royalty_info(env: &Env) -> Option<Vec<(Address, u32)>> {
     env.storage()
         .instance()
         .get(&default_royalty_key())
+        .or_else(|| {
+            env.storage()
+                .instance()
+                .get::<_, (Address, u32)>(&legacy_default_royalty_key())
+                .map(|split| Vec::from_array(env, [split]))
+        })
 }
 
 /// Delete default royalty
@@ -352,21 +480,33 @@ pub fn delete_default_royalty_info(env: &Env) {
     env.storage()
         .instance()
         .remove(&default_royalty_key());
+    env.storage()
+        .instance()
+        .remove(&legacy_default_royalty_key());
 }
 
 /// Set token-specific royalty (overrides default)
-pub fn set_token_royalty_info(env: &Env, token_id: u64, receiver: &Address, bps: u32) {
+pub fn set_token_royalty_info(env: &Env, token_id: u64, splits: &Vec<(Address, u32)>) {
     env.storage()
         .persistent()
-        .set(&(token_royalty_key(token_id), token_id), &(receiver.clone(), bps));
+        .set(&(token_royalty_key(token_id), token_id), splits);
+    env.storage()
+        .persistent()
+        .remove(&(legacy_token_royalty_key(token_id), token_id));
 }
 
 /// Get token-specific royalty
-/// Returns Option<(receiver, bps)>
-pub fn get_token_royalty_info(env: &Env, token_id: u64) -> Option<(Address, u32)> {
+/// Returns Option<Vec<(receiver, bps)>>, falling back to the legacy single receiver
+pub fn get_token_royalty_info(env: &Env, token_id: u64) -> Option<Vec<(Address, u32)>> {
     env.storage()
         .persistent()
         .get(&(token_royalty_key(token_id), token_id))
+        .or_else(|| {
+            env.storage()
+                .persistent()
+                .get::<_, (Address, u32)>(&(legacy_token_royalty_key(token_id), token_id))
+                .map(|split| Vec::from_array(env, [split]))
+        })
 }
 
 /// Delete token-specific royalty
@@ -374,6 +514,9 @@ pub fn delete_token_royalty_info(env: &Env, token_id: u64) {
     env.storage()
         .persistent()
         .remove(&(token_royalty_key(token_id), token_id));
+    env.storage()
+        .persistent()
+        .remove(&(legacy_token_royalty_key(token_id), token_id));
 }
 
 // ========== ENUMERABLE (ERC-721 Enumerable Extension) ==========
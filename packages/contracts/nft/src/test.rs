@@ -1,7 +1,7 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use soroban_sdk::{testutils::{Address as _, Events, Ledger}, Address, Bytes, Env, FromVal, IntoVal, String, Vec};
 
 fn create_nft_contract<'a>(env: &Env) -> NFTContractClient<'a> {
     NFTContractClient::new(env, &env.register_contract(None, NFTContract {}))
@@ -968,3 +968,735 @@ fn test_enumerable_batch_mint() {
         assert_eq!(tokens.get(i).unwrap(), (i + 1) as u64);
     }
 }
+
+// ========== MARKETPLACE HOOK TESTS ==========
+
+/// Records the last `on_token_moved` notification
+mod mock_marketplace {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    #[contract]
+    pub struct MockMarketplace;
+
+    #[contractimpl]
+    impl MockMarketplace {
+        pub fn on_token_moved(
+            env: Env,
+            nft_contract: Address,
+            token_id: u64,
+            from: Address,
+            to: Option<Address>,
+        ) {
+            nft_contract.require_auth();
+            env.storage()
+                .instance()
+                .set(&symbol_short!("last"), &(token_id, from, to));
+        }
+
+        pub fn last_moved(env: Env) -> Option<(u64, Address, Option<Address>)> {
+            env.storage().instance().get(&symbol_short!("last"))
+        }
+    }
+}
+
+fn setup_collection<'a>(env: &Env) -> (NFTContractClient<'a>, Address) {
+    let contract = create_nft_contract(env);
+    let admin = Address::generate(env);
+
+    contract.initialize(
+        &admin,
+        &String::from_str(env, "Test"),
+        &String::from_str(env, "TEST"),
+        &String::from_str(env, "ipfs://"),
+    );
+
+    (contract, admin)
+}
+
+fn test_metadata(env: &Env) -> TokenMetadata {
+    TokenMetadata {
+        name: String::from_str(env, "Token"),
+        description: String::from_str(env, "Test"),
+        image_uri: String::from_str(env, "ipfs://image"),
+        metadata_uri: String::from_str(env, "ipfs://metadata"),
+    }
+}
+
+fn setup_hooked_collection<'a>(
+    env: &Env,
+) -> (NFTContractClient<'a>, mock_marketplace::MockMarketplaceClient<'a>) {
+    let (contract, admin) = setup_collection(env);
+    let marketplace = mock_marketplace::MockMarketplaceClient::new(
+        env,
+        &env.register_contract(None, mock_marketplace::MockMarketplace {}),
+    );
+
+    contract.set_marketplace_hook(&admin, &Some(marketplace.address.clone()));
+    assert_eq!(contract.marketplace_hook(), Some(marketplace.address.clone()));
+
+    (contract, marketplace)
+}
+
+#[test]
+fn test_marketplace_hook_on_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, marketplace) = setup_hooked_collection(&env);
+    let from = Address::generate(&env);
+    let to = Address::generate(&env);
+
+    contract.mint(&from, &1, &test_metadata(&env));
+    contract.transfer(&from, &to, &1);
+
+    assert_eq!(marketplace.last_moved(), Some((1, from, Some(to))));
+}
+
+#[test]
+fn test_marketplace_hook_on_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, marketplace) = setup_hooked_collection(&env);
+    let owner = Address::generate(&env);
+
+    contract.mint(&owner, &1, &test_metadata(&env));
+    contract.burn(&owner, &1);
+
+    assert_eq!(marketplace.last_moved(), Some((1, owner, None)));
+}
+
+#[test]
+fn test_marketplace_hook_skipped_for_marketplace_spender() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, marketplace) = setup_hooked_collection(&env);
+    let owner = Address::generate(&env);
+    let buyer = Address::generate(&env);
+
+    contract.mint(&owner, &1, &test_metadata(&env));
+    contract.set_approval_for_all(&owner, &marketplace.address, &true);
+    contract.transfer_from(&marketplace.address, &owner, &buyer, &1);
+
+    assert_eq!(contract.owner_of(&1), buyer);
+    assert_eq!(marketplace.last_moved(), None);
+}
+
+#[test]
+fn test_marketplace_hook_skipped_for_escrow_release() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, marketplace) = setup_hooked_collection(&env);
+    let winner = Address::generate(&env);
+
+    contract.mint(&marketplace.address, &1, &test_metadata(&env));
+    contract.transfer(&marketplace.address, &winner, &1);
+
+    assert_eq!(contract.owner_of(&1), winner);
+    assert_eq!(marketplace.last_moved(), None);
+}
+
+#[test]
+fn test_set_marketplace_hook_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let other_user = Address::generate(&env);
+
+    let result = contract.try_set_marketplace_hook(&other_user, &Some(other_user.clone()));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+// ========== ROYALTY SPLIT TESTS ==========
+
+#[test]
+fn test_royalty_splits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let artist = Address::generate(&env);
+    let producer = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    contract.mint(&user, &1, &test_metadata(&env));
+
+    let splits = Vec::from_array(&env, [(artist.clone(), 500u32), (producer.clone(), 250u32)]);
+    contract.set_default_royalty_splits(&admin, &splits);
+
+    assert_eq!(
+        contract.royalty_splits(&1, &1000),
+        Vec::from_array(&env, [(artist.clone(), 50i128), (producer.clone(), 25i128)])
+    );
+
+    // Single-recipient view reports only the primary receiver's share
+    assert_eq!(contract.royalty_info(&1, &1000), (artist.clone(), 50));
+
+    // Token-specific split overrides the default
+    contract.set_token_royalty(&admin, &1, &producer, &1000);
+    assert_eq!(
+        contract.royalty_splits(&1, &1000),
+        Vec::from_array(&env, [(producer.clone(), 100i128)])
+    );
+
+    contract.reset_token_royalty(&admin, &1);
+    contract.delete_default_royalty(&admin);
+    assert_eq!(contract.royalty_splits(&1, &1000).len(), 0);
+}
+
+#[test]
+fn test_legacy_royalty_read_as_single_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let artist = Address::generate(&env);
+    let producer = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    contract.mint(&user, &1, &test_metadata(&env));
+
+    // Royalties written before splits were introduced
+    env.as_contract(&contract.address, || {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("def_roy"), &(artist.clone(), 500u32));
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("tok_roy"), 1u64), &(producer.clone(), 1000u32));
+    });
+
+    assert_eq!(
+        contract.royalty_splits(&1, &1000),
+        Vec::from_array(&env, [(producer.clone(), 100i128)])
+    );
+
+    contract.reset_token_royalty(&admin, &1);
+    assert_eq!(
+        contract.royalty_splits(&1, &1000),
+        Vec::from_array(&env, [(artist.clone(), 50i128)])
+    );
+
+    contract.delete_default_royalty(&admin);
+    assert_eq!(contract.royalty_splits(&1, &1000).len(), 0);
+}
+
+#[test]
+fn test_admin_actions_emit_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let artist = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    contract.mint(&user, &1, &test_metadata(&env));
+
+    let new_uri = String::from_str(&env, "ipfs://new/");
+    contract.set_base_uri(&admin, &new_uri);
+
+    let (emitter, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(emitter, contract.address);
+    assert_eq!(topics, (symbol_short!("base_uri"),).into_val(&env));
+    assert_eq!(String::from_val(&env, &data), new_uri);
+
+    let splits = Vec::from_array(&env, [(artist.clone(), 500u32)]);
+    contract.set_default_royalty_splits(&admin, &splits);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("roy_set"),).into_val(&env));
+    assert_eq!(
+        <(Option<u64>, Vec<(Address, u32)>)>::from_val(&env, &data),
+        (None, splits)
+    );
+
+    contract.reset_token_royalty(&admin, &1);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("roy_set"),).into_val(&env));
+    assert_eq!(
+        <(Option<u64>, Vec<(Address, u32)>)>::from_val(&env, &data),
+        (Some(1), Vec::new(&env))
+    );
+
+    let new_admin = Address::generate(&env);
+    contract.transfer_admin(&admin, &new_admin);
+
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(topics, (symbol_short!("admin_xfr"),).into_val(&env));
+    assert_eq!(Address::from_val(&env, &data), new_admin);
+}
+
+#[test]
+fn test_royalty_splits_total_capped() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let artist = Address::generate(&env);
+    let producer = Address::generate(&env);
+
+    let splits = Vec::from_array(&env, [(artist.clone(), 600u32), (producer.clone(), 500u32)]);
+    let result = contract.try_set_default_royalty_splits(&admin, &splits);
+    assert_eq!(result, Err(Ok(Error::InvalidRoyalty)));
+
+    // Same receiver listed twice
+    let splits = Vec::from_array(&env, [(artist.clone(), 100u32), (artist.clone(), 100u32)]);
+    let result = contract.try_set_default_royalty_splits(&admin, &splits);
+    assert_eq!(result, Err(Ok(Error::InvalidRoyalty)));
+}
+
+// ========== OPERATOR FILTER TESTS ==========
+
+#[test]
+fn test_operator_filter_blocks_unlisted_operators() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let owner = Address::generate(&env);
+    let marketplace = Address::generate(&env);
+    let p2p_operator = Address::generate(&env);
+
+    contract.mint(&owner, &1, &test_metadata(&env));
+
+    contract.set_operator_filter(&admin, &true);
+    contract.set_allowed_operator(&admin, &marketplace, &true);
+    assert!(contract.operator_filter_enabled());
+    assert!(contract.is_allowed_operator(&marketplace));
+
+    let result = contract.try_approve(&owner, &p2p_operator, &1);
+    assert_eq!(result, Err(Ok(Error::OperatorNotAllowed)));
+
+    let result = contract.try_set_approval_for_all(&owner, &p2p_operator, &true);
+    assert_eq!(result, Err(Ok(Error::OperatorNotAllowed)));
+
+    // Allowlisted marketplace can still be approved and move the token
+    contract.set_approval_for_all(&owner, &marketplace, &true);
+    let buyer = Address::generate(&env);
+    contract.transfer_from(&marketplace, &owner, &buyer, &1);
+    assert_eq!(contract.owner_of(&1), buyer);
+}
+
+#[test]
+fn test_operator_removed_from_allowlist_cannot_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let owner = Address::generate(&env);
+    let operator = Address::generate(&env);
+
+    contract.mint(&owner, &1, &test_metadata(&env));
+
+    // Approved before the filter was enabled
+    contract.set_approval_for_all(&owner, &operator, &true);
+    contract.set_operator_filter(&admin, &true);
+
+    let result = contract.try_transfer_from(&operator, &owner, &operator, &1);
+    assert_eq!(result, Err(Ok(Error::OperatorNotAllowed)));
+
+    // Revoking is still possible
+    contract.set_approval_for_all(&owner, &operator, &false);
+}
+
+#[test]
+fn test_set_allowed_operator_unauthorized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let not_admin = Address::generate(&env);
+    let operator = Address::generate(&env);
+
+    let result = contract.try_set_allowed_operator(&not_admin, &operator, &true);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+// ========== GOVERNANCE TESTS ==========
+
+fn enable_two_of_three(
+    env: &Env,
+    contract: &NFTContractClient,
+    admin: &Address,
+) -> (Address, Address, Address) {
+    let signer_a = Address::generate(env);
+    let signer_b = Address::generate(env);
+    let signer_c = Address::generate(env);
+
+    let mut signers = Vec::new(env);
+    signers.push_back(signer_a.clone());
+    signers.push_back(signer_b.clone());
+    signers.push_back(signer_c.clone());
+
+    contract.enable_governance(admin, &GovernanceConfig {
+        signers,
+        threshold: 2,
+        delay: 3600,
+    });
+
+    (signer_a, signer_b, signer_c)
+}
+
+#[test]
+fn test_governance_proposal_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let (signer_a, signer_b, _) = enable_two_of_three(&env, &contract, &admin);
+
+    // Direct admin calls are rejected once governance is enabled
+    let new_uri = String::from_str(&env, "ar://");
+    let result = contract.try_set_base_uri(&admin, &new_uri);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let proposal_id = contract.propose(&signer_a, &AdminAction::SetBaseUri(new_uri.clone()));
+
+    // One approval is below the threshold
+    let result = contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Ok(Error::InvalidProposal)));
+
+    contract.approve_proposal(&signer_b, &proposal_id);
+    let proposal = contract.get_proposal(&proposal_id);
+    assert_eq!(proposal.executable_at, Some(env.ledger().timestamp() + 3600));
+
+    // Timelock has not passed
+    let result = contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Ok(Error::InvalidProposal)));
+
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    contract.execute_proposal(&proposal_id);
+
+    assert_eq!(contract.base_uri(), new_uri);
+    assert_eq!(contract.get_proposal(&proposal_id).status, ProposalStatus::Executed);
+
+    // Cannot be executed twice
+    let result = contract.try_execute_proposal(&proposal_id);
+    assert_eq!(result, Err(Ok(Error::InvalidProposal)));
+}
+
+#[test]
+fn test_governance_signer_checks_and_minting() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let (signer_a, signer_b, signer_c) = enable_two_of_three(&env, &contract, &admin);
+    let outsider = Address::generate(&env);
+
+    let result = contract.try_propose(&outsider, &AdminAction::SetOperatorFilter(true));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let proposal_id = contract.propose(&signer_a, &AdminAction::SetOperatorFilter(true));
+
+    // Duplicate approval
+    let result = contract.try_approve_proposal(&signer_a, &proposal_id);
+    assert_eq!(result, Err(Ok(Error::InvalidProposal)));
+
+    contract.cancel_proposal(&signer_c, &proposal_id);
+    let result = contract.try_approve_proposal(&signer_b, &proposal_id);
+    assert_eq!(result, Err(Ok(Error::InvalidProposal)));
+
+    // Minting stays with the admin key
+    let owner = Address::generate(&env);
+    contract.mint(&owner, &1, &test_metadata(&env));
+    assert_eq!(contract.owner_of(&1), owner);
+}
+
+#[test]
+fn test_governance_disabled_by_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let (signer_a, signer_b, _) = enable_two_of_three(&env, &contract, &admin);
+
+    let proposal_id = contract.propose(&signer_a, &AdminAction::DisableGovernance);
+    contract.approve_proposal(&signer_b, &proposal_id);
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    contract.execute_proposal(&proposal_id);
+
+    assert_eq!(contract.get_governance(), None);
+    contract.set_operator_filter(&admin, &true);
+}
+
+#[test]
+fn test_governance_change_cancels_pending_proposals() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let (signer_a, signer_b, _) = enable_two_of_three(&env, &contract, &admin);
+
+    // Fully approved under the old signers, but not yet executed
+    let stale_id = contract.propose(&signer_a, &AdminAction::SetOperatorFilter(true));
+    contract.approve_proposal(&signer_b, &stale_id);
+
+    let mut signers = Vec::new(&env);
+    signers.push_back(signer_a.clone());
+    signers.push_back(Address::generate(&env));
+    let proposal_id = contract.propose(&signer_a, &AdminAction::SetGovernance(GovernanceConfig {
+        signers,
+        threshold: 1,
+        delay: 0,
+    }));
+    contract.approve_proposal(&signer_b, &proposal_id);
+    env.ledger().with_mut(|li| li.timestamp += 3600);
+    contract.execute_proposal(&proposal_id);
+
+    assert_eq!(contract.get_proposal(&stale_id).status, ProposalStatus::Cancelled);
+    let result = contract.try_execute_proposal(&stale_id);
+    assert_eq!(result, Err(Ok(Error::InvalidProposal)));
+}
+
+#[test]
+fn test_enable_governance_invalid_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, admin) = setup_collection(&env);
+    let signer = Address::generate(&env);
+
+    let mut signers = Vec::new(&env);
+    signers.push_back(signer.clone());
+    signers.push_back(signer);
+
+    let result = contract.try_enable_governance(&admin, &GovernanceConfig {
+        signers,
+        threshold: 1,
+        delay: 0,
+    });
+    assert_eq!(result, Err(Ok(Error::InvalidGovernance)));
+}
+
+// ========== BATCH TRANSFER TESTS ==========
+
+fn mint_tokens(env: &Env, contract: &NFTContractClient, owner: &Address, count: u64) -> Vec<u64> {
+    let mut token_ids = Vec::new(env);
+    for token_id in 1..=count {
+        contract.mint(owner, &token_id, &test_metadata(env));
+        token_ids.push_back(token_id);
+    }
+
+    token_ids
+}
+
+#[test]
+fn test_batch_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let owner = Address::generate(&env);
+    let cold_wallet = Address::generate(&env);
+
+    let token_ids = mint_tokens(&env, &contract, &owner, 5);
+    contract.mint(&owner, &6, &test_metadata(&env));
+
+    contract.batch_transfer(&owner, &cold_wallet, &token_ids);
+
+    assert_eq!(contract.balance_of(&owner), 1);
+    assert_eq!(contract.balance_of(&cold_wallet), 5);
+    assert_eq!(contract.tokens_of_owner(&owner), Vec::from_array(&env, [6]));
+    for token_id in token_ids.iter() {
+        assert_eq!(contract.owner_of(&token_id), cold_wallet);
+    }
+
+    // A token the sender doesn't own fails the whole batch
+    let result = contract.try_batch_transfer(&cold_wallet, &owner, &Vec::from_array(&env, [1, 6]));
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    assert_eq!(contract.owner_of(&1), cold_wallet);
+}
+
+#[test]
+fn test_batch_transfer_from_requires_approval_for_every_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let token_ids = mint_tokens(&env, &contract, &owner, 3);
+    contract.batch_approve(&owner, &spender, &Vec::from_array(&env, [1, 2]));
+    assert_eq!(contract.get_approved(&2), Some(spender.clone()));
+
+    let result = contract.try_batch_transfer_from(&spender, &owner, &recipient, &token_ids);
+    assert_eq!(result, Err(Ok(Error::NotApproved)));
+
+    contract.approve(&owner, &spender, &3);
+    contract.batch_transfer_from(&spender, &owner, &recipient, &token_ids);
+
+    assert_eq!(contract.balance_of(&owner), 0);
+    assert_eq!(contract.balance_of(&recipient), 3);
+    assert_eq!(contract.get_approved(&1), None);
+}
+
+#[test]
+fn test_batch_burn() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let owner = Address::generate(&env);
+
+    mint_tokens(&env, &contract, &owner, 4);
+    contract.batch_burn(&owner, &Vec::from_array(&env, [1, 3]));
+
+    assert_eq!(contract.balance_of(&owner), 2);
+    assert_eq!(contract.total_supply(), 2);
+    assert!(!contract.exists(&1));
+    assert!(contract.exists(&2));
+
+    // Duplicate IDs fail before anything is burned
+    let result = contract.try_batch_burn(&owner, &Vec::from_array(&env, [2, 2]));
+    assert_eq!(result, Err(Ok(Error::DuplicateTokenId)));
+    assert_eq!(contract.balance_of(&owner), 2);
+}
+
+#[test]
+fn test_batch_transfer_to_self_with_duplicate_ids_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let owner = Address::generate(&env);
+
+    contract.mint(&owner, &1, &test_metadata(&env));
+
+    let result = contract.try_batch_transfer(&owner, &owner, &Vec::from_array(&env, [1, 1]));
+    assert_eq!(result, Err(Ok(Error::DuplicateTokenId)));
+    assert_eq!(contract.balance_of(&owner), 1);
+
+    // Distinct IDs to the same owner leave the balance unchanged
+    contract.batch_transfer(&owner, &owner, &Vec::from_array(&env, [1]));
+    assert_eq!(contract.balance_of(&owner), 1);
+    assert_eq!(contract.owner_of(&1), owner);
+}
+
+#[test]
+fn test_batch_size_limits() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let owner = Address::generate(&env);
+    let recipient = Address::generate(&env);
+
+    let result = contract.try_batch_transfer(&owner, &recipient, &Vec::new(&env));
+    assert_eq!(result, Err(Ok(Error::BatchEmpty)));
+
+    let mut too_many = Vec::new(&env);
+    for token_id in 0..101 {
+        too_many.push_back(token_id);
+    }
+    let result = contract.try_batch_burn(&owner, &too_many);
+    assert_eq!(result, Err(Ok(Error::BatchTooLarge)));
+    let result = contract.try_batch_approve(&owner, &recipient, &too_many);
+    assert_eq!(result, Err(Ok(Error::BatchTooLarge)));
+}
+
+// ========== SAFE TRANSFER TESTS ==========
+
+mod mock_receiver {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Bytes, Env, Symbol};
+
+    #[contract]
+    pub struct MockReceiver;
+
+    #[contractimpl]
+    impl MockReceiver {
+        pub fn set_accept(env: Env, accept: bool) {
+            env.storage().instance().set(&symbol_short!("accept"), &accept);
+        }
+
+        pub fn on_nft_received(
+            env: Env,
+            operator: Address,
+            from: Address,
+            token_id: u64,
+            data: Bytes,
+        ) -> Symbol {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("last"), &(operator, from, token_id, data));
+
+            let accept: bool = env.storage().instance().get(&symbol_short!("accept")).unwrap_or(false);
+            if accept {
+                crate::NFT_RECEIVED
+            } else {
+                symbol_short!("no")
+            }
+        }
+
+        pub fn last_received(env: Env) -> Option<(Address, Address, u64, Bytes)> {
+            env.storage().instance().get(&symbol_short!("last"))
+        }
+    }
+}
+
+fn create_receiver<'a>(env: &Env, accept: bool) -> mock_receiver::MockReceiverClient<'a> {
+    let receiver_id = env.register_contract(None, mock_receiver::MockReceiver);
+    let receiver = mock_receiver::MockReceiverClient::new(env, &receiver_id);
+    receiver.set_accept(&accept);
+
+    receiver
+}
+
+#[test]
+fn test_safe_transfer_to_account_and_accepting_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let owner = Address::generate(&env);
+    let account = Address::from_string(&String::from_str(
+        &env,
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+    ));
+    let receiver = create_receiver(&env, true);
+    let data = Bytes::from_array(&env, &[1, 2, 3]);
+
+    contract.mint(&owner, &1, &test_metadata(&env));
+    contract.mint(&owner, &2, &test_metadata(&env));
+
+    // Accounts are not asked
+    contract.safe_transfer(&owner, &account, &1, &data);
+    assert_eq!(contract.owner_of(&1), account);
+
+    let spender = Address::generate(&env);
+    contract.approve(&owner, &spender, &2);
+    contract.safe_transfer_from(&spender, &owner, &receiver.address, &2, &data);
+
+    assert_eq!(contract.owner_of(&2), receiver.address);
+    assert_eq!(receiver.last_received(), Some((spender, owner, 2, data)));
+}
+
+#[test]
+fn test_safe_transfer_rejected_by_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (contract, _) = setup_collection(&env);
+    let owner = Address::generate(&env);
+    let receiver = create_receiver(&env, false);
+    let data = Bytes::new(&env);
+
+    contract.mint(&owner, &1, &test_metadata(&env));
+
+    let result = contract.try_safe_transfer(&owner, &receiver.address, &1, &data);
+    assert_eq!(result, Err(Ok(Error::TransferRejected)));
+
+    // Contracts without the hook reject too
+    let not_receiver = create_nft_contract(&env);
+    let result = contract.try_safe_transfer(&owner, &not_receiver.address, &1, &data);
+    assert_eq!(result, Err(Ok(Error::TransferRejected)));
+
+    assert_eq!(contract.owner_of(&1), owner);
+    assert_eq!(contract.balance_of(&owner), 1);
+}